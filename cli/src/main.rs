@@ -0,0 +1,277 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+
+use nameit_core::api::{
+    check_names, parse_name, parse_sources, validate_name, ApiError, CheckStatus, NameResult,
+};
+use nameit_core::check::BATCH_CONCURRENCY;
+use nameit_core::Cache;
+use tokio::sync::Semaphore;
+
+const USAGE: &str = "usage: nameit check [NAME]... [--source npm,github,crates] [--json]
+
+Checks whether each name is free on every source. Names are read from
+stdin, one per line, when none are given.
+
+Sources: npm, npm-org, github, github-repo, crates
+Exit status: 0 when every name is free, 1 when any is taken, 2 on
+invalid arguments and 3 when a source could not be checked.";
+
+struct Args {
+    names: Vec<String>,
+    sources: Vec<String>,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    match args.next().as_deref() {
+        Some("check") => {}
+        Some("--help" | "-h") => return Err(String::new()),
+        _ => return Err("expected the `check` command".to_string()),
+    }
+    let mut parsed = Args {
+        names: vec![],
+        sources: vec![],
+        json: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => parsed.json = true,
+            "--source" | "-s" => parsed
+                .sources
+                .push(args.next().ok_or("--source needs a value")?),
+            "--help" | "-h" => return Err(String::new()),
+            _ => match arg.strip_prefix("--source=") {
+                Some(sources) => parsed.sources.push(sources.to_string()),
+                None if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                None => parsed.names.push(arg),
+            },
+        }
+    }
+    Ok(parsed)
+}
+
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Available => "free",
+        CheckStatus::Taken => "taken",
+        CheckStatus::Similar => "similar",
+        CheckStatus::Error => "error",
+    }
+}
+
+fn write_table(out: &mut impl Write, results: &[NameResult]) -> std::io::Result<()> {
+    let Some(first) = results.first() else {
+        return Ok(());
+    };
+    let mut rows = vec![std::iter::once("name".to_string())
+        .chain(first.results.iter().map(|result| result.source.clone()))
+        .collect::<Vec<_>>()];
+    for name in results {
+        rows.push(
+            std::iter::once(name.name.clone())
+                .chain(name.results.iter().map(|result| match &result.similar_to {
+                    Some(similar) => format!("similar to {}", similar),
+                    None => status_label(result.status).to_string(),
+                }))
+                .collect(),
+        );
+    }
+    let widths = (0..rows[0].len())
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+    for row in rows {
+        let cells = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", cells.join("  ").trim_end())?;
+    }
+    for name in results {
+        for result in &name.results {
+            if let Some(error) = &result.error {
+                eprintln!("{} on {}: {}", name.name, result.source, error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 1 when any name is taken or too similar, otherwise 3 when a source couldn't be checked
+fn exit_status(results: &[NameResult]) -> u8 {
+    let statuses = results
+        .iter()
+        .flat_map(|name| name.results.iter().map(|result| result.status))
+        .collect::<Vec<_>>();
+    if statuses
+        .iter()
+        .any(|status| matches!(status, CheckStatus::Taken | CheckStatus::Similar))
+    {
+        1
+    } else if statuses.contains(&CheckStatus::Error) {
+        3
+    } else {
+        0
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            if !error.is_empty() {
+                eprintln!("error: {}\n", error);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut names = args.names;
+    if names.is_empty() {
+        let stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+        names = stdin
+            .lock()
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+    }
+    let sources = match parse_sources(&args.sources) {
+        Ok(sources) => sources,
+        Err(error) => {
+            eprintln!("error: {}", error.message);
+            return ExitCode::from(2);
+        }
+    };
+    let names = match names
+        .iter()
+        .map(|name| {
+            let name = parse_name(name)?;
+            validate_name(&name, &sources)?;
+            Ok(name)
+        })
+        .collect::<Result<Vec<_>, ApiError>>()
+    {
+        Ok(names) => names,
+        Err(error) => {
+            eprintln!("error: {}", error.message);
+            return ExitCode::from(2);
+        }
+    };
+
+    let client = match nameit_core::client() {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::from(3);
+        }
+    };
+    let limit = Semaphore::new(BATCH_CONCURRENCY);
+    let results = check_names(&client, &Cache::new(), &limit, names, &sources).await;
+    // A closed stdout, as when piped into `head`, isn't worth reporting
+    let mut out = std::io::stdout().lock();
+    let _ = if args.json {
+        serde_json::to_writer_pretty(&mut out, &results)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(out))
+    } else {
+        write_table(&mut out, &results)
+    };
+    ExitCode::from(exit_status(&results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nameit_core::check::{Availability, Sources};
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn checked(results: &[Result<Availability, &str>]) -> Vec<NameResult> {
+        let results = results
+            .iter()
+            .map(|result| {
+                let result = result.clone().map_err(str::to_string);
+                nameit_core::api::SourceResult::new(Sources::PackageNpm, result, None)
+            })
+            .collect();
+        vec![NameResult {
+            name: "nameit".to_string(),
+            results,
+        }]
+    }
+
+    #[test]
+    fn source_values() {
+        let args = parse(&[
+            "check",
+            "foo",
+            "--source=npm,crates",
+            "--source",
+            "github",
+            "-s",
+            "npm-org",
+            "bar",
+        ])
+        .unwrap();
+        assert_eq!(args.names, ["foo", "bar"]);
+        assert_eq!(args.sources, ["npm,crates", "github", "npm-org"]);
+        assert!(!args.json);
+        assert!(parse(&["check", "foo", "--json"]).unwrap().json);
+        assert!(parse(&["check", "foo", "--source"]).is_err());
+    }
+
+    #[test]
+    fn unknown_options() {
+        assert_eq!(
+            parse(&["check", "foo", "--quiet"]).err().unwrap(),
+            "unknown option `--quiet`"
+        );
+        assert!(parse(&["lookup", "foo"]).is_err());
+        assert!(parse(&[]).is_err());
+    }
+
+    #[test]
+    fn help() {
+        // An empty error prints only the usage
+        assert_eq!(parse(&["check", "--help"]).err().unwrap(), "");
+        assert_eq!(parse(&["check", "foo", "-h"]).err().unwrap(), "");
+        assert_eq!(parse(&["--help"]).err().unwrap(), "");
+    }
+
+    #[test]
+    fn exit_statuses() {
+        assert_eq!(exit_status(&checked(&[Ok(Availability::Available)])), 0);
+        assert_eq!(
+            exit_status(&checked(&[
+                Ok(Availability::Available),
+                Ok(Availability::Taken)
+            ])),
+            1
+        );
+        assert_eq!(
+            exit_status(&checked(&[Ok(Availability::Similar(
+                "name-it".to_string()
+            ))])),
+            1
+        );
+        assert_eq!(
+            exit_status(&checked(&[Ok(Availability::Available), Err("timed out")])),
+            3
+        );
+        // Taken wins over a failed source
+        assert_eq!(
+            exit_status(&checked(&[Err("timed out"), Ok(Availability::Taken)])),
+            1
+        );
+    }
+}
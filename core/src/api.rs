@@ -0,0 +1,351 @@
+//! Requests and results of name checks, shared by the HTTP API, chat commands and the CLI
+
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use crate::check::{
+    check_source, repository_parts, source_url, Availability, Sources, BATCH_CONCURRENCY,
+};
+use crate::Cache;
+
+/// Sources checked when a request names none
+pub const DEFAULT_SOURCES: [Sources; 4] = [
+    Sources::PackageNpm,
+    Sources::OrgNpm,
+    Sources::Github,
+    Sources::Crates,
+];
+
+/// Most names a single batch request may check
+pub const MAX_BATCH_NAMES: usize = 100;
+
+#[cfg(feature = "openapi")]
+pub use self::schema::{source_schema, sources_schema};
+
+#[cfg(feature = "openapi")]
+mod schema {
+    use utoipa::openapi::{schema::Object, Array, ArrayBuilder, ObjectBuilder, SchemaType};
+
+    use crate::check::Sources;
+
+    /// What each source key checks
+    fn source_description(source: Sources) -> &'static str {
+        match source {
+            Sources::PackageNpm => "npm package",
+            Sources::OrgNpm => "npm organisation scope",
+            Sources::Github => "GitHub user or organisation",
+            Sources::GithubRepo => "GitHub repository, the name must be `owner/name`",
+            Sources::Crates => "crates.io crate",
+        }
+    }
+
+    pub fn source_schema() -> Object {
+        let described = Sources::ALL
+            .map(|source| format!("`{}` {}", source.key(), source_description(source)))
+            .join(", ");
+        ObjectBuilder::new()
+            .schema_type(SchemaType::String)
+            .enum_values(Some(Sources::ALL.map(|source| source.key())))
+            .description(Some(format!("Source key: {}", described)))
+            .build()
+    }
+
+    pub fn sources_schema() -> Array {
+        ArrayBuilder::new()
+            .items(source_schema())
+            .description(Some(
+                "Sources to check, defaults to `npm`, `npm-org`, `github` and `crates`",
+            ))
+            .build()
+    }
+}
+
+/// Outcome of one check, `error` when the source could not be reached
+#[derive(Deserialize, Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Available,
+    Taken,
+    Similar,
+    Error,
+}
+
+/// Result of checking a name against one source
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SourceResult {
+    /// Source key, as accepted by the `source` parameter
+    #[cfg_attr(feature = "openapi", schema(schema_with = source_schema))]
+    pub source: String,
+    pub status: CheckStatus,
+    /// Existing package the name is too similar to, for `similar` results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similar_to: Option<String>,
+    /// Whether the result was served from the cache
+    pub cached: bool,
+    /// Seconds since the result was fetched from the source
+    pub age_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SourceResult {
+    pub fn new(
+        source: Sources,
+        result: Result<Availability, String>,
+        age_secs: Option<u64>,
+    ) -> Self {
+        let (status, similar_to, error) = match result {
+            Ok(Availability::Available) => (CheckStatus::Available, None, None),
+            Ok(Availability::Taken) => (CheckStatus::Taken, None, None),
+            Ok(Availability::Similar(name)) => (CheckStatus::Similar, Some(name), None),
+            Err(error) => (CheckStatus::Error, None, Some(error)),
+        };
+        SourceResult {
+            source: source.key().to_string(),
+            status,
+            similar_to,
+            cached: age_secs.is_some(),
+            age_secs: age_secs.unwrap_or_default(),
+            error,
+        }
+    }
+
+    /// The result as the check returned it, or why the source couldn't be checked
+    pub fn availability(&self) -> Result<Availability, String> {
+        match self.status {
+            CheckStatus::Available => Ok(Availability::Available),
+            CheckStatus::Taken => Ok(Availability::Taken),
+            CheckStatus::Similar => Ok(Availability::Similar(
+                self.similar_to.clone().unwrap_or_default(),
+            )),
+            CheckStatus::Error => Err(self.error.clone().unwrap_or_default()),
+        }
+    }
+}
+
+/// Results of checking one name, in the order the sources were requested
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NameResult {
+    pub name: String,
+    pub results: Vec<SourceResult>,
+}
+
+/// Why a request was rejected: `missing_name` when no name was given,
+/// `invalid_name` when a name is too long, contains whitespace, `?`, `#` or `%`,
+/// or a `/` outside of a `github-repo` check,
+/// `invalid_source` for an unknown source key, `too_many_names` when a
+/// batch exceeds 100 names and `invalid_body` when the body is not a batch request
+#[derive(Deserialize, Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    MissingName,
+    InvalidName,
+    InvalidSource,
+    TooManyNames,
+    InvalidBody,
+}
+
+/// Error body returned with a 400 status
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Resolves source keys, falling back to [`DEFAULT_SOURCES`] when there are none
+pub fn parse_sources(keys: &[String]) -> Result<Vec<Sources>, ApiError> {
+    if keys.is_empty() {
+        return Ok(DEFAULT_SOURCES.to_vec());
+    }
+    let mut sources = vec![];
+    for key in keys.iter().flat_map(|key| key.split(',')).map(str::trim) {
+        let source = Sources::from_key(key).ok_or_else(|| {
+            let known = Sources::ALL.map(|source| source.key()).join(", ");
+            ApiError::new(
+                ErrorCode::InvalidSource,
+                format!("unknown source `{}`, expected one of {}", key, known),
+            )
+        })?;
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    Ok(sources)
+}
+
+/// Trims a name, rejecting ones no registry could hold
+pub fn parse_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::MissingName,
+            "name must not be empty",
+        ));
+    }
+    if name.len() > 214 || name.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidName,
+            format!("`{}` is not a valid name", name),
+        ));
+    }
+    // They would end the URL path of the registry page, or escape into another one
+    if name.contains(['?', '#', '%']) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidName,
+            format!(
+                "`{}` is not a valid name, `?`, `#` and `%` aren't allowed",
+                name
+            ),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Rejects a name the sources can't hold: only `github-repo` names, which must be
+/// `owner/name`, may contain a `/`
+pub fn validate_name(name: &str, sources: &[Sources]) -> Result<(), ApiError> {
+    let repository = sources.contains(&Sources::GithubRepo);
+    if repository && repository_parts(name).is_none() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidName,
+            format!(
+                "`{}` is not a repository, `github-repo` names must be `owner/name`",
+                name
+            ),
+        ));
+    }
+    if name.contains('/') && sources.iter().any(|source| *source != Sources::GithubRepo) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidName,
+            format!(
+                "`{}` is not a valid name, only `github-repo` names may contain `/`",
+                name
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks every name against every source, keeping failed checks as errors. Each check
+/// holds a permit of `limit`, so callers sharing it share how many checks are in flight
+pub async fn check_names(
+    client: &reqwest::Client,
+    cache: &Cache,
+    limit: &Semaphore,
+    names: Vec<String>,
+    sources: &[Sources],
+) -> Vec<NameResult> {
+    let checks = names
+        .iter()
+        .flat_map(|name| sources.iter().map(move |source| (name.clone(), *source)))
+        .collect::<Vec<_>>();
+    let mut results = stream::iter(checks)
+        .map(|(name, source)| {
+            let (client, cache) = (client.clone(), cache.clone());
+            async move {
+                let _permit = limit.acquire().await;
+                let age = cache
+                    .age(source_url(source, &name))
+                    .map(|age| age.as_secs());
+                let result = check_source(&client, &cache, source, &name)
+                    .await
+                    .map_err(|error| error.to_string());
+                SourceResult::new(source, result, age)
+            }
+        })
+        .buffered(BATCH_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter();
+
+    names
+        .into_iter()
+        .map(|name| NameResult {
+            results: results.by_ref().take(sources.len()).collect(),
+            name,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_results_convert_back() {
+        let results = [
+            Ok(Availability::Available),
+            Ok(Availability::Taken),
+            Ok(Availability::Similar("name-it".to_string())),
+            Err("timed out".to_string()),
+        ];
+        for result in results {
+            let converted = SourceResult::new(Sources::PackageNpm, result.clone(), None);
+            assert_eq!(converted.availability(), result);
+        }
+    }
+
+    fn code(result: Result<impl std::fmt::Debug, ApiError>) -> ErrorCode {
+        result.unwrap_err().code
+    }
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(parse_name("  nameit \n").unwrap(), "nameit");
+        assert_eq!(code(parse_name(" \t")), ErrorCode::MissingName);
+        for name in ["name it", "name\tit", "name?it", "name#it", "name%2fit"] {
+            assert_eq!(code(parse_name(name)), ErrorCode::InvalidName, "{}", name);
+        }
+        assert!(parse_name(&"a".repeat(214)).is_ok());
+        assert_eq!(code(parse_name(&"a".repeat(215))), ErrorCode::InvalidName);
+    }
+
+    #[test]
+    fn slashes_only_in_repositories() {
+        let npm = [Sources::PackageNpm, Sources::Crates];
+        assert!(validate_name("nameit", &npm).is_ok());
+        assert_eq!(
+            code(validate_name("owner/nameit", &npm)),
+            ErrorCode::InvalidName
+        );
+        assert_eq!(
+            code(validate_name(
+                "owner/nameit",
+                &[Sources::GithubRepo, Sources::Github]
+            )),
+            ErrorCode::InvalidName
+        );
+        assert!(validate_name("owner/nameit", &[Sources::GithubRepo]).is_ok());
+        for name in [
+            "nameit",
+            "owner/",
+            "/nameit",
+            "owner/../x",
+            "../nameit",
+            "owner/..",
+            "a/b/c",
+        ] {
+            assert_eq!(
+                code(validate_name(name, &[Sources::GithubRepo])),
+                ErrorCode::InvalidName,
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    value: T,
+    duration: Duration,
+    cached_at: Instant,
+}
+
+#[derive(Debug, Clone)]
+pub enum CacheData {
+    Exists(Cached<bool>),
+    Json(Cached<String>),
+    Expired,
+    // NotFound
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Cache(Arc<Mutex<HashMap<String, CacheData>>>);
+
+impl Cache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_value(&self, key: String) -> Option<CacheData> {
+        match self.0.lock() {
+            Ok(v) => v.get(&key).cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn set_value(&self, key: String, data: CacheData) -> Option<CacheData> {
+        match self.0.lock() {
+            Ok(mut v) => v.insert(key, data),
+            _ => None,
+        }
+    }
+
+    pub fn get_exists(&self, key: String) -> Option<bool> {
+        let value = self.get_value(key.clone())?;
+        match value {
+            CacheData::Exists(cached) => {
+                if cached.cached_at.elapsed() > cached.duration {
+                    self.set_value(key, CacheData::Expired);
+                    return None;
+                }
+                Some(cached.value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Time since a still fresh entry was cached
+    pub fn age(&self, key: String) -> Option<Duration> {
+        let (cached_at, duration) = match self.get_value(key)? {
+            CacheData::Exists(cached) => (cached.cached_at, cached.duration),
+            CacheData::Json(cached) => (cached.cached_at, cached.duration),
+            CacheData::Expired => return None,
+        };
+        Some(cached_at.elapsed()).filter(|age| *age <= duration)
+    }
+
+    pub fn set_exists(&self, key: String, exists: bool) -> Option<()> {
+        self.set_value(
+            key,
+            CacheData::Exists(Cached {
+                value: exists,
+                duration: Duration::from_secs(86_400),
+                cached_at: Instant::now(),
+            }),
+        )?;
+        Some(())
+    }
+
+    pub fn get_json<T: DeserializeOwned>(&self, key: String) -> Option<T> {
+        let value = self.get_value(key.clone())?;
+        match value {
+            CacheData::Json(cached) => {
+                if cached.cached_at.elapsed() > cached.duration {
+                    self.set_value(key, CacheData::Expired);
+                    return None;
+                }
+                serde_json::from_str(&cached.value).ok()
+            }
+            _ => None,
+        }
+    }
+
+    pub fn set_json<T: Serialize>(&self, key: String, value: &T) -> Option<()> {
+        let value = serde_json::to_string(value).ok()?;
+        self.set_value(
+            key,
+            CacheData::Json(Cached {
+                value,
+                duration: Duration::from_secs(86_400),
+                cached_at: Instant::now(),
+            }),
+        );
+        Some(())
+    }
+}
@@ -0,0 +1,452 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::Cache;
+
+/// Failure to reach a source or decode its answer
+#[derive(Error, Debug)]
+pub enum CheckError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{0} responded with {1}")]
+    Status(String, http::StatusCode),
+    #[error("`{0}` is not a repository, expected `owner/name`")]
+    NotRepository(String),
+}
+
+/// Characters escaped when a name becomes a URL path segment
+pub const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Hash, Eq, Copy)]
+pub enum Sources {
+    PackageNpm,
+    OrgNpm,
+    Github,
+    /// A repository, checked with a title of the form `owner/name`
+    GithubRepo,
+    Crates,
+}
+
+impl Sources {
+    pub const ALL: [Sources; 5] = [
+        Sources::PackageNpm,
+        Sources::OrgNpm,
+        Sources::Github,
+        Sources::GithubRepo,
+        Sources::Crates,
+    ];
+
+    /// Short identifier used in URLs
+    pub fn key(&self) -> &'static str {
+        match self {
+            Sources::PackageNpm => "npm",
+            Sources::OrgNpm => "npm-org",
+            Sources::Github => "github",
+            Sources::GithubRepo => "github-repo",
+            Sources::Crates => "crates",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Sources::ALL.into_iter().find(|source| source.key() == key)
+    }
+
+    /// Registry or site the source checks
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            Sources::PackageNpm | Sources::OrgNpm => "npm",
+            Sources::Github | Sources::GithubRepo => "github",
+            Sources::Crates => "crates.io",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Sources::PackageNpm => "package",
+            Sources::OrgNpm => "org",
+            Sources::Github => "",
+            Sources::GithubRepo => "repo",
+            Sources::Crates => "crate",
+        }
+    }
+}
+
+/// Result of checking a name against a single source
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Hash, Eq)]
+pub enum Availability {
+    Available,
+    Taken,
+    /// Free, but the registry refuses it as too similar to the named package
+    Similar(String),
+}
+
+impl Availability {
+    pub fn from_available(available: bool) -> Self {
+        match available {
+            true => Availability::Available,
+            false => Availability::Taken,
+        }
+    }
+}
+
+/// Results of checking one name against several sources
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct NameAvailability {
+    pub title: String,
+    pub results: Vec<(Sources, Availability)>,
+    /// Sources that couldn't be checked, with why
+    #[serde(default)]
+    pub errors: Vec<(Sources, String)>,
+}
+
+impl NameAvailability {
+    /// Number of sources where the name is free
+    pub fn free(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, result)| *result == Availability::Available)
+            .count()
+    }
+
+    /// Whether every source was checked and none has the name free
+    pub fn taken_everywhere(&self) -> bool {
+        self.errors.is_empty() && self.free() == 0
+    }
+
+    /// Result for a single source, if it was checked
+    pub fn result(&self, source: Sources) -> Option<Availability> {
+        self.results
+            .iter()
+            .find(|(checked, _)| *checked == source)
+            .map(|(_, result)| result.clone())
+    }
+}
+
+/// npm's comparison form of a package name, lowercase with `.`, `-` and `_` removed
+pub fn npm_stripped(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, '.' | '-' | '_'))
+        .collect()
+}
+
+/// Whether `url` is missing, which for a registry page means the name is free
+pub async fn page_exists(client: &reqwest::Client, url: String) -> Result<bool, CheckError> {
+    let response = client.get(url.clone()).send().await?;
+    if response.status() == http::StatusCode::NOT_FOUND {
+        return Ok(true);
+    }
+    if !response.status().is_success() {
+        return Err(CheckError::Status(url, response.status()));
+    }
+    Ok(false)
+}
+
+/// Fetches and decodes a JSON document, `None` when the server responds with not found
+pub async fn fetch_json<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    url: String,
+) -> Result<Option<T>, CheckError> {
+    let response = client.get(url.clone()).send().await?;
+    if response.status() == http::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(CheckError::Status(url, response.status()));
+    }
+    Ok(Some(response.json::<T>().await?))
+}
+
+/// First of `names` other than `title` itself whose stripped form collides with it.
+/// Scoped packages live in their own namespace and never collide
+fn npm_collision(title: &str, names: impl IntoIterator<Item = String>) -> Option<String> {
+    let stripped = npm_stripped(title);
+    names
+        .into_iter()
+        .filter(|name| !name.starts_with('@') && *name != title.to_lowercase())
+        .find(|name| npm_stripped(name) == stripped)
+}
+
+/// Finds an existing unscoped package whose stripped form collides with `title`
+async fn npm_similar(
+    client: &reqwest::Client,
+    cache: &Cache,
+    title: &str,
+) -> Result<Option<String>, CheckError> {
+    #[derive(Deserialize)]
+    struct Package {
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Object {
+        package: Package,
+    }
+
+    #[derive(Deserialize)]
+    struct Search {
+        objects: Vec<Object>,
+    }
+
+    let stripped = npm_stripped(title);
+    let key = format!("npm-similar-{}", stripped);
+    if let Some(cached) = cache.get_json::<Option<String>>(key.clone()) {
+        return Ok(cached);
+    }
+
+    let mut similar = None;
+    if stripped != title.to_lowercase()
+        && fetch_json::<serde_json::Value>(
+            client,
+            format!(
+                "https://registry.npmjs.org/{}",
+                utf8_percent_encode(&stripped, NON_ALPHANUMERIC)
+            ),
+        )
+        .await?
+        .is_some()
+    {
+        similar = Some(stripped.clone());
+    }
+    if similar.is_none() {
+        let search = fetch_json::<Search>(
+            client,
+            format!(
+                "https://registry.npmjs.org/-/v1/search?text={}&size=250",
+                utf8_percent_encode(&stripped, NON_ALPHANUMERIC)
+            ),
+        )
+        .await?;
+        let names = search
+            .map(|search| search.objects)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|object| object.package.name);
+        similar = npm_collision(title, names);
+    }
+
+    cache.set_json(key, &similar);
+    Ok(similar)
+}
+
+async fn cached_page_exists(
+    client: &reqwest::Client,
+    cache: &Cache,
+    url: String,
+) -> Result<bool, CheckError> {
+    match cache.get_exists(url.clone()) {
+        Some(true) => return Ok(true),
+        Some(false) => return Ok(false),
+        _ => (),
+    }
+    let result = page_exists(client, url.clone()).await?;
+    cache.set_exists(url, result);
+    Ok(result)
+}
+
+/// Owner and name of a repository title of the form `owner/name`
+pub fn repository_parts(title: &str) -> Option<(&str, &str)> {
+    let (owner, name) = title.split_once('/')?;
+    let segment = |part: &str| !matches!(part, "" | "." | "..");
+    let valid = segment(owner) && segment(name) && !name.contains('/');
+    valid.then_some((owner, name))
+}
+
+/// `owner/name` with both segments escaped, the title as one segment when it isn't a repository
+fn repository_path(title: &str) -> String {
+    match repository_parts(title) {
+        Some((owner, name)) => format!(
+            "{}/{}",
+            utf8_percent_encode(owner, PATH_SEGMENT),
+            utf8_percent_encode(name, PATH_SEGMENT)
+        ),
+        None => utf8_percent_encode(title, PATH_SEGMENT).to_string(),
+    }
+}
+
+/// Page whose absence means `title` is free on `source`, also the key its result is cached under
+pub fn source_url(source: Sources, title: &str) -> String {
+    let title = title.to_lowercase();
+    let segment = utf8_percent_encode(&title, PATH_SEGMENT);
+    match source {
+        Sources::PackageNpm => format!("https://www.npmjs.com/package/{}", segment),
+        Sources::OrgNpm => format!("https://www.npmjs.com/org/{}", segment),
+        Sources::Github => format!("https://github.com/{}", segment),
+        Sources::GithubRepo => format!("https://github.com/{}", repository_path(&title)),
+        Sources::Crates => format!("https://crates.io/api/v1/crates/{}", segment),
+    }
+}
+
+/// Page showing whoever holds `title` on `source`
+pub fn registry_url(source: Sources, title: &str) -> String {
+    let title = title.to_lowercase();
+    let segment = utf8_percent_encode(&title, PATH_SEGMENT);
+    match source {
+        Sources::PackageNpm => format!("https://www.npmjs.com/package/{}", segment),
+        Sources::OrgNpm => format!("https://www.npmjs.com/org/{}", segment),
+        Sources::Github => format!("https://github.com/{}", segment),
+        Sources::GithubRepo => format!("https://github.com/{}", repository_path(&title)),
+        Sources::Crates => format!("https://crates.io/crates/{}", segment),
+    }
+}
+
+/// Naming rules of `source` that `title` breaks, empty when it could be registered
+pub fn name_problems(source: Sources, title: &str) -> Vec<String> {
+    let only = |text: &str, allowed: &dyn Fn(char) -> bool| !text.chars().all(allowed);
+    let (mut problems, rules) = match source {
+        Sources::PackageNpm | Sources::OrgNpm => (
+            vec![],
+            vec![
+                (title.len() > 214, "npm names can be at most 214 characters"),
+                (
+                    title.chars().any(char::is_uppercase),
+                    "npm names can't contain capital letters",
+                ),
+                (
+                    title.starts_with(['.', '_']),
+                    "npm names can't start with a dot or an underscore",
+                ),
+                (
+                    only(title, &|c| {
+                        c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+                    }),
+                    "npm names can only contain letters, digits, `-`, `.`, `_` and `~`",
+                ),
+            ],
+        ),
+        Sources::Github => (
+            vec![],
+            vec![
+                (
+                    title.len() > 39,
+                    "GitHub accounts can be at most 39 characters",
+                ),
+                (
+                    only(title, &|c| c.is_ascii_alphanumeric() || c == '-'),
+                    "GitHub accounts can only contain letters, digits and `-`",
+                ),
+                (
+                    title.starts_with('-') || title.ends_with('-') || title.contains("--"),
+                    "GitHub accounts can't start or end with `-` or contain `--`",
+                ),
+            ],
+        ),
+        Sources::GithubRepo => match repository_parts(title) {
+            Some((owner, repository)) => (
+                name_problems(Sources::Github, owner),
+                vec![
+                    (
+                        repository.len() > 100,
+                        "GitHub repositories can be at most 100 characters",
+                    ),
+                    (
+                        only(repository, &|c| {
+                            c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_')
+                        }),
+                        "GitHub repositories can only contain letters, digits, `-`, `.` and `_`",
+                    ),
+                ],
+            ),
+            None => (
+                vec![],
+                vec![(true, "GitHub repositories are checked as `owner/name`")],
+            ),
+        },
+        Sources::Crates => (
+            vec![],
+            vec![
+                (title.len() > 64, "crate names can be at most 64 characters"),
+                (
+                    !title.starts_with(|c: char| c.is_ascii_alphabetic()),
+                    "crate names must start with a letter",
+                ),
+                (
+                    only(title, &|c| {
+                        c.is_ascii_alphanumeric() || matches!(c, '-' | '_')
+                    }),
+                    "crate names can only contain letters, digits, `-` and `_`",
+                ),
+            ],
+        ),
+    };
+    problems.extend(
+        rules
+            .into_iter()
+            .filter(|(broken, _)| *broken)
+            .map(|(_, problem)| problem.to_string()),
+    );
+    problems
+}
+
+/// Checks `title` against a single source
+pub async fn check_source(
+    client: &reqwest::Client,
+    cache: &Cache,
+    source: Sources,
+    title: &str,
+) -> Result<Availability, CheckError> {
+    if source == Sources::GithubRepo && repository_parts(title).is_none() {
+        return Err(CheckError::NotRepository(title.to_string()));
+    }
+    let available = cached_page_exists(client, cache, source_url(source, title)).await?;
+
+    if available && source == Sources::PackageNpm {
+        // The package page already showed the name is free, the search only narrows that down
+        match npm_similar(client, cache, title).await {
+            Ok(Some(similar)) => return Ok(Availability::Similar(similar)),
+            Ok(None) => (),
+            Err(error) => log::warn!("npm similarity search for {} failed: {}", title, error),
+        }
+    }
+    Ok(Availability::from_available(available))
+}
+
+/// Maximum number of checks a batch keeps in flight
+pub const BATCH_CONCURRENCY: usize = 8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn stripped_names() {
+        assert_eq!(npm_stripped("Name-It"), "nameit");
+        assert_eq!(npm_stripped("name_it.js"), "nameitjs");
+        assert_eq!(npm_stripped("-._"), "");
+        assert_eq!(npm_stripped("nameit"), "nameit");
+    }
+
+    #[test]
+    fn collisions() {
+        assert_eq!(
+            npm_collision("name-it", names(&["nameit-cli", "name_it", "nameit"])),
+            Some("name_it".to_string())
+        );
+        assert_eq!(
+            npm_collision("NameIt", names(&["name.it"])),
+            Some("name.it".to_string())
+        );
+    }
+
+    #[test]
+    fn ignored_collisions() {
+        // The queried name itself and scoped packages don't block it
+        assert_eq!(
+            npm_collision(
+                "Name-It",
+                names(&["name-it", "@scope/nameit", "@name-it/core"])
+            ),
+            None
+        );
+        assert_eq!(npm_collision("nameit", names(&["nameit-js", "name"])), None);
+    }
+}
@@ -0,0 +1,322 @@
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::LazyLock};
+
+use crate::corpus::{fetch_page, CorpusError, Entry, FetchFuture, Flag, MediaWiki, NameCorpus};
+
+/// A corpus scraped with CSS selectors, as read from a definitions file
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CorpusDefinition {
+    pub id: String,
+    pub title: String,
+    /// Page the entries are scraped from, relative links resolve against it. `/wiki/`
+    /// articles are fetched through the MediaWiki API rather than as rendered HTML
+    pub url: String,
+    /// MediaWiki Action API endpoint, defaults to the wiki's `/w/api.php`
+    pub api: Option<String>,
+    /// Lists the articles of the category page at `url` instead of scraping it
+    #[serde(default)]
+    pub category: bool,
+    /// Selects one element per entry
+    #[serde(default)]
+    pub items: String,
+    /// Element within an item holding the title, the item itself when unset
+    pub name: Option<String>,
+    /// Cuts the title at its first occurrence
+    pub separator: Option<String>,
+    /// Element within an item whose `href` links the entry, defaults to the name element
+    pub link: Option<String>,
+    /// Image within an item whose `src` illustrates the entry
+    pub image: Option<String>,
+    /// Flag icons within an item, each holding an `a` and an `img`
+    pub flags: Option<String>,
+    /// Skips items without any flag
+    #[serde(default)]
+    pub require_flags: bool,
+}
+
+static FLAG_LINK: LazyLock<Selector> = LazyLock::new(|| Selector::parse("a").unwrap());
+static FLAG_IMAGE: LazyLock<Selector> = LazyLock::new(|| Selector::parse("img").unwrap());
+
+fn first<'a>(item: ElementRef<'a>, selector: &Option<Selector>) -> Option<ElementRef<'a>> {
+    match selector {
+        Some(selector) => item.select(selector).next(),
+        None => Some(item),
+    }
+}
+
+fn clean_title(title: &str) -> String {
+    // Drop footnote markers such as `[1]`
+    let title = match title.find('[') {
+        Some(index) => &title[..index],
+        None => title,
+    };
+    title.trim().to_string()
+}
+
+impl CorpusDefinition {
+    /// Parses every selector once, so broken definitions fail at startup
+    pub fn compile(self) -> Result<SelectorCorpus, CorpusError> {
+        if self.items.is_empty() && !self.category {
+            return Err(CorpusError::MissingItems(self.id.clone()));
+        }
+        let compile = |selector: Option<&String>| -> Result<Option<Selector>, CorpusError> {
+            selector
+                .map(|selector| {
+                    Selector::parse(selector)
+                        .map_err(|_| CorpusError::Selector(self.id.clone(), selector.clone()))
+                })
+                .transpose()
+        };
+        Ok(SelectorCorpus {
+            items: compile((!self.category).then_some(&self.items))?,
+            name: compile(self.name.as_ref())?,
+            link: compile(self.link.as_ref())?,
+            image: compile(self.image.as_ref())?,
+            flags: compile(self.flags.as_ref())?,
+            definition: self,
+        })
+    }
+}
+
+/// A definition along with its compiled selectors
+#[derive(Debug)]
+pub struct SelectorCorpus {
+    pub definition: CorpusDefinition,
+    items: Option<Selector>,
+    name: Option<Selector>,
+    link: Option<Selector>,
+    image: Option<Selector>,
+    flags: Option<Selector>,
+}
+
+impl SelectorCorpus {
+    fn resolve(&self, href: &str) -> Option<String> {
+        let url = reqwest::Url::parse(&self.definition.url)
+            .ok()?
+            .join(href)
+            .ok()?;
+        Some(url.to_string())
+    }
+
+    pub fn scrape_flag(&self, flag: ElementRef<'_>) -> Option<Flag> {
+        let a = flag.select(&FLAG_LINK).next()?;
+        let src = flag
+            .select(&FLAG_IMAGE)
+            .next()
+            .and_then(|img| img.value().attr("src"))?;
+
+        Some(Flag {
+            title: a.value().attr("title")?.to_string(),
+            link: self.resolve(a.value().attr("href")?)?,
+            src: self.resolve(src)?,
+        })
+    }
+
+    pub fn scrape_entry(&self, item: ElementRef<'_>) -> Option<Entry> {
+        let name = first(item, &self.name)?;
+        let text = name.text().collect::<String>();
+        let title = match &self.definition.separator {
+            Some(separator) => clean_title(text.split(separator.as_str()).next()?),
+            None => clean_title(&text),
+        };
+        if title.is_empty() {
+            return None;
+        }
+
+        let link = match self.link {
+            Some(_) => first(item, &self.link),
+            None => Some(name),
+        }
+        .and_then(|a| a.value().attr("href"))
+        .and_then(|href| self.resolve(href))
+        .unwrap_or(self.definition.url.clone());
+        let image = self
+            .image
+            .as_ref()
+            .and_then(|_| first(item, &self.image))
+            .and_then(|img| img.value().attr("src"))
+            .and_then(|src| self.resolve(src));
+
+        let mut flags: Vec<Flag> = vec![];
+        if let Some(selector) = &self.flags {
+            for flag in item
+                .select(selector)
+                .filter_map(|flag| self.scrape_flag(flag))
+            {
+                if !flags.iter().any(|f| f.src == flag.src) {
+                    flags.push(flag);
+                }
+            }
+        }
+        if self.definition.require_flags && flags.is_empty() {
+            return None;
+        }
+
+        Some(Entry {
+            link,
+            title,
+            image,
+            flags,
+        })
+    }
+}
+
+impl NameCorpus for SelectorCorpus {
+    fn id(&self) -> &str {
+        &self.definition.id
+    }
+
+    fn title(&self) -> &str {
+        &self.definition.title
+    }
+
+    fn url(&self) -> &str {
+        &self.definition.url
+    }
+
+    fn fetch(&self, client: &reqwest::Client) -> FetchFuture {
+        let client = client.clone();
+        let wiki = MediaWiki::for_article(&self.definition.url, self.definition.api.as_deref());
+        let category = self.definition.category;
+        let url = self.definition.url.clone();
+        Box::pin(async move {
+            match wiki {
+                // One title per line
+                Some((wiki, page)) if category => {
+                    Ok(wiki.category_members(&client, &page).await?.join("\n"))
+                }
+                Some((wiki, page)) => wiki.parse(&client, &page).await,
+                None if category => Err(CorpusError::NotCategory(url)),
+                None => fetch_page(client, url).await,
+            }
+        })
+    }
+
+    fn parse(&self, document: &str) -> Vec<Entry> {
+        let Some(items) = &self.items else {
+            return document
+                .lines()
+                .filter_map(|title| {
+                    let link = self.resolve(&format!("/wiki/{}", title.replace(' ', "_")))?;
+                    Some(Entry {
+                        link,
+                        title: title.to_string(),
+                        image: None,
+                        flags: vec![],
+                    })
+                })
+                .collect();
+        };
+        let document = Html::parse_document(document);
+        let mut seen = HashSet::new();
+        document
+            .select(items)
+            .filter_map(|item| self.scrape_entry(item))
+            .filter(|entry| seen.insert(entry.title.to_lowercase()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = r#"<table><tbody>
+        <tr><td><span class="flagicon"><a href="/wiki/United_Kingdom" title="United Kingdom"><img src="//upload.example.org/uk.svg"></a></span></td>
+            <td class="name"><a href="/wiki/Operation_Dynamo">Dynamo – Dunkirk evacuation[1]</a></td></tr>
+        <tr><td><span class="flagicon"><a href="/wiki/United_States" title="United States"><img src="/flags/us.svg"></a></span><span class="flagicon"><a href="/wiki/United_States" title="United States"><img src="/flags/us.svg"></a></span></td>
+            <td class="name"><a href="https://other.example/torch">Torch</a></td></tr>
+        <tr><td></td><td class="name"><a href="/wiki/Unflagged">Unflagged</a></td></tr>
+        <tr><td><span class="flagicon"><a href="/wiki/France" title="France"><img src="/flags/fr.svg"></a></span></td>
+            <td class="name"><a href="/wiki/Dynamo_(repeat)">dynamo</a></td></tr>
+    </tbody></table>"#;
+
+    fn definition() -> CorpusDefinition {
+        CorpusDefinition {
+            id: "operations".to_string(),
+            title: "Operations".to_string(),
+            url: "https://wiki.example.org/wiki/List_of_operations".to_string(),
+            api: None,
+            category: false,
+            items: "tr".to_string(),
+            name: Some("td.name a".to_string()),
+            separator: Some(" – ".to_string()),
+            link: None,
+            image: None,
+            flags: Some(".flagicon".to_string()),
+            require_flags: true,
+        }
+    }
+
+    fn flag(title: &str, link: &str, src: &str) -> Flag {
+        Flag {
+            title: title.to_string(),
+            link: link.to_string(),
+            src: src.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_fixture() {
+        let entries = definition().compile().unwrap().parse(FIXTURE);
+        assert_eq!(
+            entries,
+            [
+                Entry {
+                    link: "https://wiki.example.org/wiki/Operation_Dynamo".to_string(),
+                    title: "Dynamo".to_string(),
+                    image: None,
+                    flags: vec![flag(
+                        "United Kingdom",
+                        "https://wiki.example.org/wiki/United_Kingdom",
+                        "https://upload.example.org/uk.svg"
+                    )],
+                },
+                Entry {
+                    link: "https://other.example/torch".to_string(),
+                    title: "Torch".to_string(),
+                    image: None,
+                    flags: vec![flag(
+                        "United States",
+                        "https://wiki.example.org/wiki/United_States",
+                        "https://wiki.example.org/flags/us.svg"
+                    )],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_selector() {
+        let definition = CorpusDefinition {
+            name: Some("td..name".to_string()),
+            ..definition()
+        };
+        assert!(matches!(
+            definition.compile(),
+            Err(CorpusError::Selector(id, selector)) if id == "operations" && selector == "td..name"
+        ));
+    }
+
+    #[test]
+    fn requires_items() {
+        let missing = CorpusDefinition {
+            items: String::new(),
+            ..definition()
+        };
+        assert!(
+            matches!(missing.compile(), Err(CorpusError::MissingItems(id)) if id == "operations")
+        );
+        // Category corpora list articles instead of scraping items
+        let category = CorpusDefinition {
+            url: "https://wiki.example.org/wiki/Category:Operations".to_string(),
+            items: String::new(),
+            category: true,
+            name: None,
+            flags: None,
+            ..definition()
+        };
+        assert!(category.compile().is_ok());
+    }
+}
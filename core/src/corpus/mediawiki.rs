@@ -0,0 +1,200 @@
+use percent_encoding::percent_decode_str;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::collections::HashMap;
+
+use crate::corpus::CorpusError;
+
+/// Overrides the endpoint of every wiki, e.g. to serve fixtures from a local server
+pub const ENDPOINT_VAR: &str = "NAMEIT_MEDIAWIKI_API";
+
+/// Client for a MediaWiki Action API endpoint such as `https://en.wikipedia.org/w/api.php`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaWiki {
+    pub endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    code: String,
+    info: String,
+}
+
+impl From<ApiError> for CorpusError {
+    fn from(error: ApiError) -> Self {
+        CorpusError::MediaWiki(error.code, error.info)
+    }
+}
+
+#[derive(Deserialize)]
+struct Parsed {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct ParseResponse {
+    parse: Option<Parsed>,
+    error: Option<ApiError>,
+}
+
+#[derive(Deserialize)]
+struct Member {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct Members {
+    categorymembers: Vec<Member>,
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    #[serde(rename = "continue")]
+    next: Option<HashMap<String, String>>,
+    query: Option<Members>,
+    error: Option<ApiError>,
+}
+
+/// Text of a `parse` response
+fn parsed_text(response: ParseResponse, page: &str) -> Result<String, CorpusError> {
+    if let Some(error) = response.error {
+        return Err(error.into());
+    }
+    response
+        .parse
+        .map(|parsed| parsed.text)
+        .ok_or_else(|| CorpusError::MissingText(page.to_string()))
+}
+
+/// Adds the titles of a `categorymembers` batch, returning the parameters that ask for the
+/// next batch or `None` after the last one
+fn read_members(
+    response: QueryResponse,
+    titles: &mut Vec<String>,
+) -> Result<Option<HashMap<String, String>>, CorpusError> {
+    if let Some(error) = response.error {
+        return Err(error.into());
+    }
+    if let Some(query) = response.query {
+        titles.extend(query.categorymembers.into_iter().map(|member| member.title));
+    }
+    Ok(response.next)
+}
+
+impl MediaWiki {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        MediaWiki {
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// Client and page title for a `/wiki/` article url, the endpoint defaults to the
+    /// article's `/w/api.php`
+    pub fn for_article(url: &str, endpoint: Option<&str>) -> Option<(Self, String)> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let page = url.path().strip_prefix("/wiki/")?;
+        let page = percent_decode_str(page).decode_utf8().ok()?.to_string();
+        let endpoint = std::env::var(ENDPOINT_VAR)
+            .ok()
+            .or(endpoint.map(String::from))
+            .unwrap_or_else(|| format!("{}/w/api.php", url.origin().ascii_serialization()));
+        Some((MediaWiki::new(endpoint), page))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        client: &reqwest::Client,
+        params: &[(&str, &str)],
+    ) -> Result<T, CorpusError> {
+        let response = client
+            .get(&self.endpoint)
+            .query(&[("format", "json"), ("formatversion", "2")])
+            .query(params)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Rendered HTML of a page, wrapped in the usual `.mw-parser-output` element
+    pub async fn parse(&self, client: &reqwest::Client, page: &str) -> Result<String, CorpusError> {
+        let params = [
+            ("action", "parse"),
+            ("page", page),
+            ("prop", "text"),
+            ("redirects", "1"),
+        ];
+        parsed_text(self.get(client, &params).await?, page)
+    }
+
+    /// Titles of the articles in a category, following continuation until the last batch
+    pub async fn category_members(
+        &self,
+        client: &reqwest::Client,
+        category: &str,
+    ) -> Result<Vec<String>, CorpusError> {
+        let mut titles = vec![];
+        let mut next: HashMap<String, String> = HashMap::new();
+        loop {
+            let mut params = vec![
+                ("action", "query"),
+                ("list", "categorymembers"),
+                ("cmtitle", category),
+                ("cmnamespace", "0"),
+                ("cmlimit", "max"),
+            ];
+            params.extend(
+                next.iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str())),
+            );
+            match read_members(self.get(client, &params).await?, &mut titles)? {
+                Some(continuation) => next = continuation,
+                None => return Ok(titles),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Responses as en.wikipedia.org sends them for `format=json&formatversion=2`, member
+    // lists cut short
+    const PARSE: &str = r#"{"parse":{"title":"List of Greek mythological figures","pageid":1160950,"text":"<div class=\"mw-content-ltr mw-parser-output\" lang=\"en\" dir=\"ltr\"><ul><li><a href=\"/wiki/Zeus\" title=\"Zeus\">Zeus</a></li></ul></div>"}}"#;
+    const MISSING: &str = r#"{"error":{"code":"missingtitle","info":"The page you specified doesn't exist.","docref":"See https://en.wikipedia.org/w/api.php for API usage."},"servedby":"mw-api-ext.eqiad.main-7d7c5b8c9f-x2q4k"}"#;
+    const FIRST_BATCH: &str = r#"{"batchcomplete":true,"continue":{"cmcontinue":"page|4d494d4f5341|1811830","continue":"-||"},"query":{"categorymembers":[{"pageid":19331,"ns":0,"title":"Moon"},{"pageid":2402,"ns":0,"title":"Amalthea (moon)"}]}}"#;
+    const LAST_BATCH: &str = r#"{"batchcomplete":true,"query":{"categorymembers":[{"pageid":1811830,"ns":0,"title":"Mimosa"}]}}"#;
+
+    #[test]
+    fn parse_reads_text() {
+        let text = parsed_text(serde_json::from_str(PARSE).unwrap(), "page").unwrap();
+        assert!(text.starts_with("<div class=\"mw-content-ltr mw-parser-output\""));
+        assert!(text.contains("href=\"/wiki/Zeus\""));
+    }
+
+    #[test]
+    fn parse_reports_api_errors() {
+        let error = parsed_text(serde_json::from_str(MISSING).unwrap(), "page").unwrap_err();
+        assert!(matches!(error, CorpusError::MediaWiki(code, _) if code == "missingtitle"));
+    }
+
+    #[test]
+    fn category_members_follow_continuation() {
+        let mut titles = vec![];
+        let next = read_members(serde_json::from_str(FIRST_BATCH).unwrap(), &mut titles)
+            .unwrap()
+            .unwrap();
+        assert_eq!(next["cmcontinue"], "page|4d494d4f5341|1811830");
+        assert_eq!(next["continue"], "-||");
+
+        let next = read_members(serde_json::from_str(LAST_BATCH).unwrap(), &mut titles).unwrap();
+        assert_eq!(next, None);
+        assert_eq!(titles, ["Moon", "Amalthea (moon)", "Mimosa"]);
+    }
+
+    #[test]
+    fn category_members_report_api_errors() {
+        let error = read_members(serde_json::from_str(MISSING).unwrap(), &mut vec![]).unwrap_err();
+        assert!(matches!(error, CorpusError::MediaWiki(code, _) if code == "missingtitle"));
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[cfg(feature = "scrape")]
+mod definition;
+mod mediawiki;
+
+#[cfg(feature = "scrape")]
+pub use self::definition::*;
+pub use self::mediawiki::*;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Flag {
+    pub title: String,
+    pub link: String,
+    pub src: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Entry {
+    pub link: String,
+    pub title: String,
+    #[serde(default)]
+    pub image: Option<String>,
+    pub flags: Vec<Flag>,
+}
+
+// Requests aren't `Send` in the browser
+#[cfg(not(target_arch = "wasm32"))]
+pub type FetchFuture = futures::future::BoxFuture<'static, Result<String, CorpusError>>;
+#[cfg(target_arch = "wasm32")]
+pub type FetchFuture = futures::future::LocalBoxFuture<'static, Result<String, CorpusError>>;
+
+/// A list of existing names the `Names` grid can be filled from
+pub trait NameCorpus: Send + Sync {
+    /// Stable identifier used in `/corpus/:id` routes
+    fn id(&self) -> &str;
+
+    fn title(&self) -> &str;
+
+    /// Page the corpus is scraped from
+    fn url(&self) -> &str;
+
+    /// Downloads the raw document `parse` reads entries from
+    fn fetch(&self, client: &reqwest::Client) -> FetchFuture {
+        Box::pin(fetch_page(client.clone(), self.url().to_string()))
+    }
+
+    fn parse(&self, document: &str) -> Vec<Entry>;
+}
+
+/// Downloads a page as is
+pub async fn fetch_page(client: reqwest::Client, url: String) -> Result<String, CorpusError> {
+    Ok(client.get(url).send().await?.text().await?)
+}
+
+/// Identifier and title of a corpus, for pickers
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CorpusInfo {
+    pub id: String,
+    pub title: String,
+}
+
+impl From<&dyn NameCorpus> for CorpusInfo {
+    fn from(corpus: &dyn NameCorpus) -> Self {
+        CorpusInfo {
+            id: corpus.id().to_string(),
+            title: corpus.title().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CorpusError {
+    #[error("couldn't read corpus definitions: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "scrape")]
+    #[error("invalid corpus definitions: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid corpus definitions: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("corpus {0} has an invalid selector `{1}`")]
+    Selector(String, String),
+    #[error("corpus {0} needs an items selector")]
+    MissingItems(String),
+    #[error("corpus {0} is defined more than once")]
+    Duplicate(String),
+    #[error("corpus definitions were already loaded")]
+    AlreadyLoaded,
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("MediaWiki {0}: {1}")]
+    MediaWiki(String, String),
+    #[error("MediaWiki returned no text for {0}")]
+    MissingText(String),
+    #[error("{0} is not a MediaWiki category")]
+    NotCategory(String),
+}
+
+#[cfg(feature = "scrape")]
+#[derive(Deserialize)]
+struct Definitions {
+    corpus: Vec<CorpusDefinition>,
+}
+
+/// Parses and compiles a definitions file, `[[corpus]]` tables in TOML or a `corpus` array
+/// in JSON
+#[cfg(feature = "scrape")]
+pub fn parse_definitions(text: &str, json: bool) -> Result<Vec<SelectorCorpus>, CorpusError> {
+    let definitions: Definitions = match json {
+        true => serde_json::from_str(text)?,
+        false => toml::from_str(text)?,
+    };
+    let mut ids = std::collections::HashSet::new();
+    let mut corpora = vec![];
+    for definition in definitions.corpus {
+        if !ids.insert(definition.id.clone()) {
+            return Err(CorpusError::Duplicate(definition.id));
+        }
+        corpora.push(definition.compile()?);
+    }
+    Ok(corpora)
+}
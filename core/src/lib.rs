@@ -0,0 +1,22 @@
+//! Name availability checks and corpus scrapers, free of any web framework so other
+//! services can embed them. Every request goes through a client the caller passes in,
+//! see [`client`], and results are memoized in a [`Cache`] the caller owns.
+
+pub mod api;
+pub mod cache;
+pub mod check;
+pub mod corpus;
+
+pub use self::cache::Cache;
+
+/// Identifies nameit to registries that require a descriptive client
+pub const USER_AGENT: &str = concat!(
+    "nameit/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Pingid/nameit)"
+);
+
+/// A client sending nameit's user agent, as registries and wikis expect
+pub fn client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder().user_agent(USER_AGENT).build()
+}
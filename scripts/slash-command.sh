@@ -0,0 +1,35 @@
+#!/bin/sh
+# Replays a signed slash command against a running server, e.g.
+#   NAMEIT_SLACK_SIGNING_SECRET=secret scripts/slash-command.sh slack foo bar
+#   NAMEIT_MATTERMOST_TOKEN=token scripts/slash-command.sh mattermost foo
+# Set NAMEIT_URL to target another server and RESPONSE_URL to receive delayed results.
+set -eu
+
+platform="${1:?usage: slash-command.sh slack|mattermost NAME...}"
+shift
+url="${NAMEIT_URL:-http://127.0.0.1:3000}/chat/$platform"
+text=$(printf '%s' "$*" | sed 's/%/%25/g; s/&/%26/g; s/+/%2B/g; s/=/%3D/g; s/,/%2C/g; s/ /+/g')
+response_url=$(printf '%s' "${RESPONSE_URL:-}" | sed 's/:/%3A/g; s#/#%2F#g')
+body="command=%2Fnameit&text=$text&response_url=$response_url"
+
+case "$platform" in
+slack)
+    secret="${NAMEIT_SLACK_SIGNING_SECRET:?set the signing secret the server uses}"
+    timestamp=$(date +%s)
+    signature=$(printf 'v0:%s:%s' "$timestamp" "$body" | openssl dgst -sha256 -hmac "$secret" | sed 's/^.* //')
+    exec curl -sS "$url" \
+        -H "X-Slack-Request-Timestamp: $timestamp" \
+        -H "X-Slack-Signature: v0=$signature" \
+        --data "$body"
+    ;;
+mattermost)
+    token="${NAMEIT_MATTERMOST_TOKEN:?set the token the server uses}"
+    exec curl -sS "$url" \
+        -H "Authorization: Token $token" \
+        --data "$body&token=$token"
+    ;;
+*)
+    echo "unknown platform $platform, expected slack or mattermost" >&2
+    exit 2
+    ;;
+esac
@@ -0,0 +1,193 @@
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+pub use nameit_core::api::{
+    check_names, parse_name, parse_sources, validate_name, ApiError, CheckStatus, ErrorCode,
+    NameResult, SourceResult, DEFAULT_SOURCES, MAX_BATCH_NAMES,
+};
+#[cfg(feature = "ssr")]
+pub use nameit_core::api::{source_schema, sources_schema};
+
+/// Query parameters of `GET /v1/check`
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "ssr", into_params(parameter_in = Query))]
+pub struct CheckParams {
+    /// Name to check
+    pub name: String,
+    /// Sources to check, repeated or comma separated
+    #[cfg_attr(feature = "ssr", param(schema_with = sources_schema))]
+    pub source: Option<Vec<String>>,
+}
+
+/// Body of `POST /v1/check`
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct BatchRequest {
+    /// Names to check, at most 100
+    pub names: Vec<String>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ssr", schema(schema_with = sources_schema))]
+    pub sources: Vec<String>,
+}
+
+/// Response of `POST /v1/check`
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct BatchResponse {
+    pub results: Vec<NameResult>,
+}
+
+cfg_if! { if #[cfg(feature = "ssr")] {
+    use axum::{
+        extract::{rejection::JsonRejection, RawQuery, State},
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use utoipa::OpenApi;
+    use utoipa_swagger_ui::SwaggerUi;
+
+    use crate::history::History;
+    use crate::state::{AppCache, CheckLimit};
+
+    /// An [`ApiError`] sent back with a 400 status
+    #[derive(Debug)]
+    pub struct BadRequest(pub ApiError);
+
+    impl From<ApiError> for BadRequest {
+        fn from(error: ApiError) -> Self {
+            BadRequest(error)
+        }
+    }
+
+    impl IntoResponse for BadRequest {
+        fn into_response(self) -> Response {
+            (StatusCode::BAD_REQUEST, Json(self.0)).into_response()
+        }
+    }
+
+    /// Checks one name
+    #[utoipa::path(
+        get,
+        path = "/v1/check",
+        tag = "check",
+        params(CheckParams),
+        responses(
+            (status = 200, description = "One result per requested source", body = NameResult),
+            (status = 400, description = "The name or a source is invalid", body = ApiError),
+        )
+    )]
+    pub async fn check_one(
+        State(client): State<reqwest::Client>,
+        State(cache): State<AppCache>,
+        State(history): State<History>,
+        State(limit): State<CheckLimit>,
+        RawQuery(query): RawQuery,
+    ) -> Result<Json<NameResult>, BadRequest> {
+        let mut name = None;
+        let mut sources = vec![];
+        for (key, value) in form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+            match key.as_ref() {
+                "name" => name = Some(value.into_owned()),
+                "source" => sources.push(value.into_owned()),
+                _ => (),
+            }
+        }
+        let name = parse_name(&name.unwrap_or_default())?;
+        let sources = parse_sources(&sources)?;
+        validate_name(&name, &sources)?;
+
+        let mut results = check_names(&client, &cache, &limit.0, vec![name], &sources).await;
+        history.record_names(&results);
+        Ok(Json(results.remove(0)))
+    }
+
+    /// Checks a batch of names
+    #[utoipa::path(
+        post,
+        path = "/v1/check",
+        tag = "check",
+        request_body = BatchRequest,
+        responses(
+            (status = 200, description = "One result per name, in request order", body = BatchResponse),
+            (status = 400, description = "The body, a name or a source is invalid", body = ApiError),
+        )
+    )]
+    pub async fn check_batch(
+        State(client): State<reqwest::Client>,
+        State(cache): State<AppCache>,
+        State(history): State<History>,
+        State(limit): State<CheckLimit>,
+        body: Result<Json<BatchRequest>, JsonRejection>,
+    ) -> Result<Json<BatchResponse>, BadRequest> {
+        let Json(request) = body.map_err(|rejection| ApiError::new(ErrorCode::InvalidBody, rejection.body_text()))?;
+        if request.names.is_empty() {
+            return Err(ApiError::new(ErrorCode::MissingName, "names must not be empty").into());
+        }
+        if request.names.len() > MAX_BATCH_NAMES {
+            return Err(ApiError::new(
+                ErrorCode::TooManyNames,
+                format!("at most {} names can be checked at once", MAX_BATCH_NAMES),
+            )
+            .into());
+        }
+        let sources = parse_sources(&request.sources)?;
+        let names = request
+            .names
+            .iter()
+            .map(|name| {
+                let name = parse_name(name)?;
+                validate_name(&name, &sources)?;
+                Ok(name)
+            })
+            .collect::<Result<Vec<_>, ApiError>>()?;
+
+        let results = check_names(&client, &cache, &limit.0, names, &sources).await;
+        history.record_names(&results);
+        Ok(Json(BatchResponse { results }))
+    }
+
+    #[derive(OpenApi)]
+    #[openapi(
+        info(
+            title = "nameit",
+            description = "Checks whether names are free on package registries and code hosts. \
+                Results are cached for a day, `cached` and `age_secs` tell how fresh each one is.",
+            license(name = "MIT")
+        ),
+        tags(
+            (name = "check", description = "Name availability checks"),
+            (name = "watch", description = "Names rechecked on a schedule, with notifications when they change"),
+            (name = "history", description = "Every result recorded for a name over time")
+        ),
+        paths(
+            check_one,
+            check_batch,
+            crate::watch::list_watched,
+            crate::watch::watch,
+            crate::watch::unwatch,
+            crate::history::export_history
+        ),
+        components(schemas(
+            CheckStatus,
+            SourceResult,
+            NameResult,
+            BatchRequest,
+            BatchResponse,
+            ErrorCode,
+            ApiError,
+            crate::watch::WatchEntry,
+            crate::watch::WatchRequest,
+            crate::history::Span,
+            crate::history::SourceHistory,
+            crate::history::NameHistory
+        ))
+    )]
+    pub struct ApiDoc;
+
+    /// Swagger UI at `/docs`, reading the spec it also serves at `/openapi.json`
+    pub fn docs() -> SwaggerUi {
+        SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi())
+    }
+}}
@@ -1,8 +1,11 @@
-use crate::components::{AppError, ErrorTemplate, Names, QueryInput};
+use crate::components::{
+    AppError, CorpusPicker, ErrorTemplate, Generator, Importer, Names, QueryInput, Report,
+};
 use leptos::*;
 use leptos_meta::*;
 use leptos_query::*;
 use leptos_router::*;
+use percent_encoding::percent_decode_str;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -19,9 +22,19 @@ pub fn App() -> impl IntoView {
             outside_errors.insert_with_default_key(AppError::NotFound);
             view! { <ErrorTemplate outside_errors/> }.into_view()
         }>
+            <nav class="flex gap-3 px-6 pt-3 text-sm">
+                <A href="/" exact=true active_class="underline">"Corpora"</A>
+                <A href="/generate" active_class="underline">"Generate"</A>
+                <A href="/import" active_class="underline">"Import"</A>
+                <a href="/docs" rel="external">"API"</a>
+            </nav>
             <main>
                 <Routes>
                     <Route path="" view=Landing/>
+                    <Route path="/corpus/:id" view=Landing/>
+                    <Route path="/generate" view=Generate/>
+                    <Route path="/import" view=Import/>
+                    <Route path="/name/:name" view=NamePage ssr=SsrMode::Async/>
                 </Routes>
             </main>
         </Router>
@@ -32,11 +45,71 @@ pub fn App() -> impl IntoView {
 #[component]
 fn Landing() -> impl IntoView {
     let (query_value, set_query_value) = create_query_signal::<String>("q");
+    let (owners_value, set_owners_value) = create_query_signal::<String>("owners");
+    let params = use_params_map();
+    // Empty without an id, which the server resolves to its default corpus
+    let corpus = Signal::derive(move || {
+        params
+            .with(|params| params.get("id").cloned())
+            .unwrap_or_default()
+    });
 
     view! {
         <div class="">
-            <QueryInput query_value set_query_value/>
-            <Names set_query_value/>
+            <QueryInput query_value set_query_value owners_value set_owners_value/>
+            <CorpusPicker corpus/>
+            <Names corpus query=Signal::derive(move || query_value().unwrap_or_default()) set_query_value/>
+        </div>
+    }
+}
+
+/// Word list generator page
+#[component]
+fn Generate() -> impl IntoView {
+    let (query_value, set_query_value) = create_query_signal::<String>("q");
+    let (owners_value, set_owners_value) = create_query_signal::<String>("owners");
+
+    view! {
+        <div class="">
+            <QueryInput query_value set_query_value owners_value set_owners_value/>
+            <Generator set_query_value/>
+        </div>
+    }
+}
+
+/// Candidate list import page
+#[component]
+fn Import() -> impl IntoView {
+    let (query_value, set_query_value) = create_query_signal::<String>("q");
+    let (owners_value, set_owners_value) = create_query_signal::<String>("owners");
+
+    view! {
+        <div class="">
+            <QueryInput query_value set_query_value owners_value set_owners_value/>
+            <Importer set_query_value/>
+        </div>
+    }
+}
+
+/// Report on one name, rendered in full on the server so it can be linked to
+#[component]
+fn NamePage() -> impl IntoView {
+    let params = use_params_map();
+    let name = Signal::derive(move || {
+        params.with(|params| {
+            // Links escape the whole name, see `name_path`. The router hands over the raw
+            // path on a page load and a `decodeURI`d one, `%2F` still escaped, after moving
+            // within the app. Names never contain `%`, so only this decode changes anything.
+            let name = params.get("name").cloned().unwrap_or_default();
+            percent_decode_str(&name).decode_utf8_lossy().into_owned()
+        })
+    });
+
+    view! {
+        <Title text=move || format!("{} - Name it", name())/>
+        <div class="flex flex-col gap-4 px-6 py-4">
+            <h1 class="text-2xl font-bold">{name}</h1>
+            <Report name/>
         </div>
     }
 }
@@ -0,0 +1,214 @@
+use cfg_if::cfg_if;
+
+use crate::api::{CheckStatus, SourceResult};
+use crate::components::Sources;
+
+/// A colored run of a badge, optionally led by a registry glyph
+pub struct Segment {
+    pub text: String,
+    pub color: &'static str,
+    pub icon: Option<&'static str>,
+}
+
+const LABEL_COLOR: &str = "#555";
+const HEIGHT: u32 = 20;
+const PADDING: u32 = 6;
+const ICON_SIZE: u32 = 14;
+const ICON_GAP: u32 = 3;
+
+/// White glyph of a registry, drawn in a 16 by 16 box
+fn icon_path(source: Sources) -> &'static str {
+    match source.icon_name() {
+        "npm" => "M0 5h16v6H8v1H4v-1H0z",
+        "github" => "M8 0C3.58 0 0 3.58 0 8c0 3.54 2.29 6.53 5.47 7.59.4.07.55-.17.55-.38 0-.19-.01-.82-.01-1.49-2.01.37-2.53-.49-2.69-.94-.09-.23-.48-.94-.82-1.13-.28-.15-.68-.52-.01-.53.63-.01 1.08.58 1.23.82.72 1.21 1.87.87 2.33.66.07-.52.28-.87.51-1.07-1.78-.2-3.64-.89-3.64-3.95 0-.87.31-1.59.82-2.15-.08-.2-.36-1.02.08-2.12 0 0 .67-.21 2.2.82.64-.18 1.32-.27 2-.27.68 0 1.36.09 2 .27 1.53-1.04 2.2-.82 2.2-.82.44 1.1.16 1.92.08 2.12.51.56.82 1.27.82 2.15 0 3.07-1.87 3.75-3.65 3.95.29.25.54.73.54 1.48 0 1.07-.01 1.93-.01 2.2 0 .21.15.46.55.38A8.013 8.013 0 0016 8c0-4.42-3.58-8-8-8z",
+        _ => "M8 1l6.5 3.5v7L8 15l-6.5-3.5v-7zM8 2.7L3.7 5 8 7.3 12.3 5z",
+    }
+}
+
+/// Registry and kind of a source, e.g. `npm package`
+pub fn source_label(source: Sources) -> String {
+    format!("{} {}", source.icon_name(), source.label())
+        .trim()
+        .to_string()
+}
+
+fn status_color(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Available => "#4c1",
+        CheckStatus::Taken => "#e05d44",
+        CheckStatus::Similar => "#dfb317",
+        CheckStatus::Error => "#9f9f9f",
+    }
+}
+
+fn status_text(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Available => "available",
+        CheckStatus::Taken => "taken",
+        CheckStatus::Similar => "too similar",
+        CheckStatus::Error => "unknown",
+    }
+}
+
+/// Approximate width of 11px Verdana, close enough to size the segments
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | '\'' | '|' => 3,
+            'f' | 'r' | 't' | ' ' | '-' | '/' | '(' | ')' => 5,
+            'm' | 'w' | 'M' | 'W' => 10,
+            c if c.is_uppercase() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Renders segments side by side in the flat shields.io style
+pub fn render(segments: &[Segment]) -> String {
+    let title = escape(
+        &segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" | "),
+    );
+    let mut rects = String::new();
+    let mut content = String::new();
+    let mut x = 0;
+    for segment in segments {
+        let icon = segment.icon.map_or(0, |_| ICON_SIZE + ICON_GAP);
+        let width = PADDING * 2 + icon + text_width(&segment.text);
+        rects += &format!(
+            r#"<rect x="{}" width="{}" height="{}" fill="{}"/>"#,
+            x, width, HEIGHT, segment.color
+        );
+        if let Some(path) = segment.icon {
+            content += &format!(
+                r##"<path transform="translate({} 3) scale(0.875)" fill="#fff" fill-rule="evenodd" d="{}"/>"##,
+                x + PADDING,
+                path
+            );
+        }
+        let text = escape(&segment.text);
+        let center = x + PADDING + icon + text_width(&segment.text) / 2;
+        content += &format!(
+            r##"<text x="{0}" y="15" fill="#010101" fill-opacity=".3" text-anchor="middle">{1}</text><text x="{0}" y="14" text-anchor="middle">{1}</text>"##,
+            center, text
+        );
+        x += width;
+    }
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" role="img" aria-label="{title}">"#,
+            r#"<title>{title}</title>"#,
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+            r##"<clipPath id="r"><rect width="{w}" height="{h}" rx="3" fill="#fff"/></clipPath>"##,
+            r#"<g clip-path="url(#r)">{rects}<rect width="{w}" height="{h}" fill="url(#s)"/></g>"#,
+            r##"<g fill="#fff" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">{content}</g>"##,
+            "</svg>"
+        ),
+        w = x,
+        h = HEIGHT,
+        title = title,
+        rects = rects,
+        content = content
+    )
+}
+
+/// Label with the source, then the result of checking the name on it
+pub fn source_badge(result: &SourceResult) -> Vec<Segment> {
+    let source = Sources::from_key(&result.source);
+    vec![
+        Segment {
+            text: source.map_or_else(|| result.source.clone(), source_label),
+            color: LABEL_COLOR,
+            icon: source.map(icon_path),
+        },
+        Segment {
+            text: status_text(result.status).to_string(),
+            color: status_color(result.status),
+            icon: None,
+        },
+    ]
+}
+
+/// The name, then one segment per source colored by its result
+pub fn summary_badge(name: &str, results: &[SourceResult]) -> Vec<Segment> {
+    let mut segments = vec![Segment {
+        text: name.to_string(),
+        color: LABEL_COLOR,
+        icon: None,
+    }];
+    for result in results {
+        let source = Sources::from_key(&result.source);
+        segments.push(Segment {
+            text: source.map_or_else(|| result.source.clone(), source_label),
+            color: status_color(result.status),
+            icon: source.map(icon_path),
+        });
+    }
+    segments
+}
+
+cfg_if! { if #[cfg(feature = "ssr")] {
+    use axum::{
+        extract::{Path, State},
+        http::{header, StatusCode},
+        response::{IntoResponse, Response},
+    };
+
+    use crate::api::{check_names, parse_name, parse_sources, validate_name, BadRequest, DEFAULT_SOURCES};
+    use crate::state::{AppCache, CheckLimit};
+
+    /// How long clients and proxies may reuse a badge, shorter when a source failed
+    const MAX_AGE: &str = "public, max-age=1800, stale-while-revalidate=86400";
+    const MAX_AGE_UNKNOWN: &str = "public, max-age=60";
+
+    /// `GET /badge/:source/*name`, where `source` is a source key, several separated by
+    /// commas or `all`, and the name ends in `.svg`
+    pub async fn badge(
+        State(client): State<reqwest::Client>,
+        State(cache): State<AppCache>,
+        State(limit): State<CheckLimit>,
+        Path((source, file)): Path<(String, String)>,
+    ) -> Result<Response, BadRequest> {
+        let Some(name) = file.trim_start_matches('/').strip_suffix(".svg") else {
+            return Ok(StatusCode::NOT_FOUND.into_response());
+        };
+        let name = parse_name(name)?;
+        let sources = match source.as_str() {
+            "all" => DEFAULT_SOURCES.to_vec(),
+            _ => parse_sources(std::slice::from_ref(&source))?,
+        };
+        validate_name(&name, &sources)?;
+
+        let results = check_names(&client, &cache, &limit.0, vec![name.clone()], &sources)
+            .await
+            .remove(0)
+            .results;
+        let segments = match results.as_slice() {
+            [result] => source_badge(result),
+            _ => summary_badge(&name, &results),
+        };
+        let failed = results
+            .iter()
+            .any(|result| result.status == CheckStatus::Error);
+
+        Ok((
+            [
+                (header::CONTENT_TYPE, "image/svg+xml"),
+                (header::CACHE_CONTROL, if failed { MAX_AGE_UNKNOWN } else { MAX_AGE }),
+            ],
+            render(&segments),
+        )
+            .into_response())
+    }
+}}
@@ -0,0 +1,380 @@
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    parse_name, validate_name, ApiError, CheckStatus, ErrorCode, NameResult, DEFAULT_SOURCES,
+};
+use crate::badge::source_label;
+use crate::components::Sources;
+
+/// Signing secret of the Slack app, Slack commands are refused when unset or empty
+pub const SLACK_SECRET_VAR: &str = "NAMEIT_SLACK_SIGNING_SECRET";
+/// Token of the Mattermost slash command, Mattermost commands are refused when unset or empty
+pub const MATTERMOST_TOKEN_VAR: &str = "NAMEIT_MATTERMOST_TOKEN";
+
+/// Most names a single command may check
+pub const MAX_COMMAND_NAMES: usize = 10;
+
+/// Chat platform a command came from, they differ only in markup
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+    Slack,
+    Mattermost,
+}
+
+impl Platform {
+    fn bold(&self, text: &str) -> String {
+        match self {
+            Platform::Slack => format!("*{}*", text),
+            Platform::Mattermost => format!("**{}**", text),
+        }
+    }
+}
+
+/// Message posted back to the channel, in the shape both platforms accept
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct CommandReply {
+    /// `in_channel` to show everyone, `ephemeral` for the caller only
+    pub response_type: String,
+    pub text: String,
+}
+
+impl CommandReply {
+    pub fn in_channel(text: String) -> Self {
+        CommandReply {
+            response_type: "in_channel".to_string(),
+            text,
+        }
+    }
+
+    pub fn ephemeral(text: String) -> Self {
+        CommandReply {
+            response_type: "ephemeral".to_string(),
+            text,
+        }
+    }
+}
+
+/// Names in the command text, separated by spaces or commas
+pub fn command_names(text: &str) -> Result<Vec<String>, ApiError> {
+    let names = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let name = parse_name(name)?;
+            validate_name(&name, &DEFAULT_SOURCES)?;
+            Ok(name)
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+    if names.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::MissingName,
+            "Usage: /nameit <name> [more names]",
+        ));
+    }
+    if names.len() > MAX_COMMAND_NAMES {
+        return Err(ApiError::new(
+            ErrorCode::TooManyNames,
+            format!("At most {} names can be checked at once", MAX_COMMAND_NAMES),
+        ));
+    }
+    Ok(names)
+}
+
+/// One line per source under each name, e.g. `:x: npm package taken`
+pub fn format_summary(platform: Platform, results: &[NameResult]) -> String {
+    let mut lines = vec![];
+    for name in results {
+        let free = name
+            .results
+            .iter()
+            .filter(|result| result.status == CheckStatus::Available)
+            .count();
+        lines.push(format!(
+            "{} is free on {} of {} sources",
+            platform.bold(&name.name),
+            free,
+            name.results.len()
+        ));
+        for result in &name.results {
+            let label = Sources::from_key(&result.source)
+                .map_or_else(|| result.source.clone(), source_label);
+            let (emoji, status) = match (&result.status, &result.similar_to) {
+                (CheckStatus::Available, _) => (":white_check_mark:", "available".to_string()),
+                (CheckStatus::Taken, _) => (":x:", "taken".to_string()),
+                (CheckStatus::Similar, Some(similar)) => {
+                    (":warning:", format!("too similar to `{}`", similar))
+                }
+                (CheckStatus::Similar, None) => (":warning:", "too similar".to_string()),
+                (CheckStatus::Error, _) => (":grey_question:", "couldn't be checked".to_string()),
+            };
+            lines.push(format!("{} {} {}", emoji, label, status));
+        }
+    }
+    lines.join("\n")
+}
+
+cfg_if! { if #[cfg(feature = "ssr")] {
+    use axum::{
+        body::Bytes,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use std::{collections::HashMap, time::Duration};
+    use subtle::ConstantTimeEq;
+
+    use crate::api::check_names;
+    use crate::history::History;
+    use crate::state::{AppCache, CheckLimit};
+    use crate::store::unix_now;
+
+    /// Requests signed longer ago than this are refused as possible replays
+    const MAX_SIGNATURE_AGE: u64 = 5 * 60;
+    /// Slack drops replies after three seconds, slower checks are posted to `response_url`
+    const REPLY_DEADLINE: Duration = Duration::from_millis(2500);
+
+    /// Checks the `X-Slack-Signature` of a request, an HMAC-SHA256 of
+    /// `v0:<timestamp>:<body>` keyed with the signing secret
+    pub fn verify_slack_signature(secret: &str, headers: &HeaderMap, body: &[u8], now: u64) -> bool {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let (Some(timestamp), Some(signature)) =
+            (header("x-slack-request-timestamp"), header("x-slack-signature"))
+        else {
+            return false;
+        };
+        let Ok(sent_at) = timestamp.parse::<u64>() else {
+            return false;
+        };
+        if now.abs_diff(sent_at) > MAX_SIGNATURE_AGE {
+            return false;
+        }
+        let Some(signature) = signature
+            .strip_prefix("v0=")
+            .and_then(|signature| hex::decode(signature).ok())
+        else {
+            return false;
+        };
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(format!("v0:{}:", timestamp).as_bytes());
+        mac.update(body);
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    /// Compares the command token Mattermost sends in the body and `Authorization` header
+    pub fn verify_mattermost_token(token: &str, headers: &HeaderMap, form: &HashMap<String, String>) -> bool {
+        let sent = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Token "))
+            .or(form.get("token").map(String::as_str));
+        // Constant time, so the token can't be guessed byte by byte
+        sent.is_some_and(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())))
+    }
+
+    /// Replies right away when the checks are quick, otherwise acknowledges and posts the
+    /// summary to `response_url` once they finish
+    async fn run_command(
+        platform: Platform,
+        client: reqwest::Client,
+        cache: AppCache,
+        history: History,
+        limit: CheckLimit,
+        form: HashMap<String, String>,
+    ) -> Response {
+        let text = form.get("text").map(String::as_str).unwrap_or_default();
+        let names = match command_names(text) {
+            Ok(names) => names,
+            Err(error) => return Json(CommandReply::ephemeral(error.message)).into_response(),
+        };
+
+        let mut checks = {
+            let (client, cache, names) = (client.clone(), cache.clone(), names.clone());
+            tokio::spawn(async move {
+                let results = check_names(&client, &cache, &limit.0, names, &DEFAULT_SOURCES).await;
+                history.record_names(&results);
+                results
+            })
+        };
+        // Awaited by reference, so checks still running past the deadline carry on
+        match tokio::time::timeout(REPLY_DEADLINE, &mut checks).await {
+            Ok(Ok(results)) => {
+                return Json(CommandReply::in_channel(format_summary(platform, &results)))
+                    .into_response()
+            }
+            Ok(Err(error)) => {
+                log::error!("chat command checks failed: {}", error);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+            Err(_) => (),
+        }
+
+        let Some(response_url) = form.get("response_url").filter(|url| !url.is_empty()).cloned() else {
+            return Json(CommandReply::ephemeral(
+                "Checks are taking a while and there is no response_url to post them to".to_string(),
+            ))
+            .into_response();
+        };
+        tokio::spawn(async move {
+            let Ok(results) = checks.await else {
+                return;
+            };
+            let reply = CommandReply::in_channel(format_summary(platform, &results));
+            if let Err(error) = client.post(&response_url).json(&reply).send().await {
+                log::warn!("couldn't post chat command results: {}", error);
+            }
+        });
+        Json(CommandReply::ephemeral(format!(
+            "Checking {} on {} sources, results will follow",
+            names.join(", "),
+            DEFAULT_SOURCES.len()
+        )))
+        .into_response()
+    }
+
+    fn parse_form(body: &[u8]) -> HashMap<String, String> {
+        form_urlencoded::parse(body).into_owned().collect()
+    }
+
+    /// `POST /chat/slack`, a Slack slash command
+    pub async fn slack_command(
+        State(client): State<reqwest::Client>,
+        State(cache): State<AppCache>,
+        State(history): State<History>,
+        State(limit): State<CheckLimit>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response {
+        let Some(secret) = std::env::var(SLACK_SECRET_VAR).ok().filter(|v| !v.is_empty()) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        if !verify_slack_signature(&secret, &headers, &body, unix_now()) {
+            return (StatusCode::UNAUTHORIZED, "invalid signature").into_response();
+        }
+        run_command(Platform::Slack, client, cache, history, limit, parse_form(&body)).await
+    }
+
+    /// `POST /chat/mattermost`, a Mattermost slash command
+    pub async fn mattermost_command(
+        State(client): State<reqwest::Client>,
+        State(cache): State<AppCache>,
+        State(history): State<History>,
+        State(limit): State<CheckLimit>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> Response {
+        let Some(token) = std::env::var(MATTERMOST_TOKEN_VAR).ok().filter(|v| !v.is_empty()) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        let form = parse_form(&body);
+        if !verify_mattermost_token(&token, &headers, &form) {
+            return (StatusCode::UNAUTHORIZED, "invalid token").into_response();
+        }
+        run_command(Platform::Mattermost, client, cache, history, limit, form).await
+    }
+}}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use axum::http::HeaderMap;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    const SECRET: &str = "8f742231b10e8888abcd99yyyzzz85a5";
+    const BODY: &[u8] = b"command=%2Fnameit&text=nameit&response_url=https%3A%2F%2Fhooks.slack.com";
+    const NOW: u64 = 1_700_000_000;
+
+    fn signed(body: &[u8], timestamp: u64) -> HeaderMap {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:", timestamp).as_bytes());
+        mac.update(body);
+        let signature = format!("v0={}", hex::encode(mac.finalize().into_bytes()));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-slack-request-timestamp",
+            timestamp.to_string().parse().unwrap(),
+        );
+        headers.insert("x-slack-signature", signature.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn valid_signature() {
+        assert!(verify_slack_signature(
+            SECRET,
+            &signed(BODY, NOW),
+            BODY,
+            NOW + 10
+        ));
+    }
+
+    #[test]
+    fn tampered_body() {
+        let headers = signed(BODY, NOW);
+        assert!(!verify_slack_signature(
+            SECRET,
+            &headers,
+            b"command=%2Fnameit&text=other",
+            NOW
+        ));
+    }
+
+    #[test]
+    fn stale_timestamp() {
+        let sent_at = NOW - MAX_SIGNATURE_AGE - 1;
+        assert!(!verify_slack_signature(
+            SECRET,
+            &signed(BODY, sent_at),
+            BODY,
+            NOW
+        ));
+    }
+
+    const TOKEN: &str = "xq1b9nd7ofy6pgk3dr5z8a4tce";
+
+    fn token_form(token: &str) -> HashMap<String, String> {
+        HashMap::from([("token".to_string(), token.to_string())])
+    }
+
+    #[test]
+    fn accepted_token() {
+        assert!(verify_mattermost_token(
+            TOKEN,
+            &HeaderMap::new(),
+            &token_form(TOKEN)
+        ));
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", format!("Token {}", TOKEN).parse().unwrap());
+        assert!(verify_mattermost_token(TOKEN, &headers, &HashMap::new()));
+    }
+
+    #[test]
+    fn wrong_token() {
+        let wrong = TOKEN.replace('x', "y");
+        assert!(!verify_mattermost_token(
+            TOKEN,
+            &HeaderMap::new(),
+            &token_form(&wrong)
+        ));
+        assert!(!verify_mattermost_token(
+            TOKEN,
+            &HeaderMap::new(),
+            &token_form("xq1b")
+        ));
+    }
+
+    #[test]
+    fn missing_token() {
+        assert!(!verify_mattermost_token(
+            TOKEN,
+            &HeaderMap::new(),
+            &HashMap::new()
+        ));
+    }
+}
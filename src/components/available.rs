@@ -1,72 +1,92 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::components::Badge;
+use crate::components::{Badge, HolderDetails, SourceIcon};
 use crate::signals::debounce_signal;
+use cfg_if::cfg_if;
 use leptos::*;
-use serde::{Deserialize, Serialize};
+pub use nameit_core::check::{
+    fetch_json, npm_stripped, page_exists, Availability, NameAvailability, Sources,
+};
+pub use nameit_core::USER_AGENT;
 
-#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Hash, Eq, Copy)]
-pub enum Sources {
-    PackageNpm,
-    OrgNpm,
-    Github,
-}
-
-pub async fn page_exists(url: String) -> Result<bool, ServerFnError> {
-    let result = reqwest::get(url).await;
-    match result {
-        Ok(result) => {
-            if http::StatusCode::NOT_FOUND == result.status() {
-                return Ok(true);
-            }
-            return Ok(false);
-        }
-        Err(_) => Ok(false),
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::state::{AppCache, CheckLimit};
     }
 }
 
+/// Checks the name typed into the query input, recording the result in the history
 #[server(CheckAvailable, "/api")]
-pub async fn check_available(source: Sources, title: String) -> Result<bool, ServerFnError> {
-    use crate::state::*;
+pub async fn check_available(
+    source: Sources,
+    title: String,
+) -> Result<Availability, ServerFnError> {
+    use crate::api::SourceResult;
+    use crate::history::History;
+    use nameit_core::check::{check_source, source_url};
 
-    async fn cached_page_exists(url: String) -> Result<bool, ServerFnError> {
-        let cache = use_context::<AppCache>().expect("Missing context provider");
-        match cache.get_exists(url.clone()) {
-            Some(true) => return Ok(true),
-            Some(false) => return Ok(false),
-            _ => (),
-        }
-        let result = page_exists(url.clone()).await?;
-        cache.set_exists(url, result.clone());
-        Ok(result)
+    let cache = use_context::<AppCache>().expect("Missing context provider");
+    let client = use_context::<reqwest::Client>().expect("Missing context provider");
+    let limit = use_context::<CheckLimit>().expect("Missing context provider");
+    let age = cache
+        .age(source_url(source, &title))
+        .map(|age| age.as_secs());
+    let _permit = limit.0.acquire().await;
+    let result = check_source(&client, &cache, source, &title).await?;
+    if let Some(history) = use_context::<History>() {
+        let recorded = SourceResult::new(source, Ok(result.clone()), age);
+        history.record([(title.as_str(), &recorded)]);
     }
+    Ok(result)
+}
 
-    match source {
-        Sources::PackageNpm => {
-            cached_page_exists(format!(
-                "https://www.npmjs.com/package/{}",
-                title.to_lowercase()
-            ))
-            .await
-        }
-        Sources::OrgNpm => {
-            cached_page_exists(format!(
-                "https://www.npmjs.com/org/{}",
-                title.to_lowercase()
-            ))
-            .await
-        }
-        Sources::Github => {
-            cached_page_exists(format!("https://github.com/{}", title.to_lowercase())).await
-        }
+/// Most titles a single [`check_many`] call may check
+pub const MAX_CHECK_TITLES: usize = 100;
+
+/// Checks every title against every source, keeping failed checks as errors
+#[server(CheckMany, "/api")]
+pub async fn check_many(
+    #[server(default)] sources: Vec<Sources>,
+    #[server(default)] titles: Vec<String>,
+) -> Result<Vec<NameAvailability>, ServerFnError> {
+    use crate::api::check_names;
+
+    if titles.len() > MAX_CHECK_TITLES {
+        return Err(ServerFnError::Args(format!(
+            "at most {} titles can be checked at once",
+            MAX_CHECK_TITLES
+        )));
     }
+    let cache = use_context::<AppCache>().expect("Missing context provider");
+    let client = use_context::<reqwest::Client>().expect("Missing context provider");
+    let limit = use_context::<CheckLimit>().expect("Missing context provider");
+    let checked = check_names(&client, &cache, &limit.0, titles, &sources).await;
+
+    Ok(checked
+        .into_iter()
+        .map(|name| {
+            let (mut results, mut errors) = (vec![], vec![]);
+            // Results come in the order of `sources`
+            for (source, result) in sources.iter().zip(&name.results) {
+                match result.availability() {
+                    Ok(availability) => results.push((*source, availability)),
+                    Err(error) => errors.push((*source, error)),
+                }
+            }
+            NameAvailability {
+                title: name.name,
+                results,
+                errors,
+            }
+        })
+        .collect())
 }
 
 /// Renders the npm package availability
 #[component]
 pub fn Available(source: Sources, query: Memo<String>) -> impl IntoView {
     let debounced = debounce_signal(std::time::Duration::from_millis(300), query);
-    let cache: Rc<RefCell<HashMap<String, bool>>> = Rc::new(RefCell::new(HashMap::new()));
+    let cache: Rc<RefCell<HashMap<String, Availability>>> = Rc::new(RefCell::new(HashMap::new()));
 
     let once = create_resource(debounced, {
         move |query| {
@@ -81,9 +101,8 @@ pub fn Available(source: Sources, query: Memo<String>) -> impl IntoView {
                 }
                 let result = check_available(source, query.clone()).await;
                 let mut cache = cache.borrow_mut();
-                if let Ok(v) = result {
-                    cache.insert(key(), v);
-                    return Ok(v);
+                if let Ok(v) = &result {
+                    cache.insert(key(), v.clone());
                 }
                 result
             }
@@ -95,35 +114,36 @@ pub fn Available(source: Sources, query: Memo<String>) -> impl IntoView {
         _ => None,
     };
 
-    let match_source = move || match source {
-        Sources::PackageNpm => (
-            "icon-[devicon--npm-wordmark]".to_string(),
-            "package".to_string(),
-        ),
-        Sources::OrgNpm => (
-            "icon-[devicon--npm-wordmark]".to_string(),
-            "org".to_string(),
-        ),
-        Sources::Github => ("icon-[devicon--github]".to_string(), "".to_string()),
+    let holder = move || match available() {
+        Some(Availability::Taken) => Some(debounced()),
+        Some(Availability::Similar(name)) => Some(name),
+        _ => None,
     };
 
+    let match_source = move || (source.icon().to_string(), source.label().to_string());
+
     view! {
-        <Suspense fallback=move || {
-            view! {
+        <div class="flex flex-col gap-1">
+            <Suspense fallback=move || {
+                view! {
+                    <Badge
+                        icon=match_source().0
+                        label=match_source().1
+                        loading=once.loading()
+                        available=move || None
+                    />
+                }
+            }>
                 <Badge
                     icon=match_source().0
                     label=match_source().1
                     loading=once.loading()
-                    available=move || None
+                    available=available
                 />
-            }
-        }>
-            <Badge
-                icon=match_source().0
-                label=match_source().1
-                loading=once.loading()
-                available=available
-            />
-        </Suspense>
+            </Suspense>
+            <Show when=move || holder().is_some()>
+                <HolderDetails source query=Signal::derive(move || holder().unwrap_or_default())/>
+            </Show>
+        </div>
     }
 }
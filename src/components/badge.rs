@@ -1,23 +1,45 @@
+use crate::components::{Availability, Sources};
 use leptos::*;
 
+/// Tailwind classes drawing the logo of a source
+pub trait SourceIcon {
+    fn icon(&self) -> &'static str;
+}
+
+impl SourceIcon for Sources {
+    fn icon(&self) -> &'static str {
+        match self {
+            Sources::PackageNpm | Sources::OrgNpm => "icon-[devicon--npm-wordmark]",
+            Sources::Github | Sources::GithubRepo => "icon-[devicon--github]",
+            Sources::Crates => "icon-[devicon--rust]",
+        }
+    }
+}
+
 /// Renders the npm package availability
 #[component]
-pub fn Badge<L: Fn() -> bool + 'static, A: Fn() -> Option<bool> + 'static>(
+pub fn Badge<L: Fn() -> bool + 'static, A: Fn() -> Option<Availability> + 'static>(
     icon: String,
     loading: L,
     available: A,
-    #[prop(default = "".to_string())] label: String,
+    #[prop(default = "".into(), into)] label: MaybeSignal<String>,
 ) -> impl IntoView {
+    let available = create_memo(move |_| available());
     let status = create_memo(move |_| {
         match loading() {
         true => "icon-[svg-spinners--270-ring-with-bg] w-5 h-5 relative top-[2px] left-[2px] text-black/70",
         false => match available() {
-            Some(true) => "icon-[heroicons--check-circle] w-6 h-6 text-green-800",
-            Some(false) => "icon-[heroicons--x-circle] w-6 h-6 text-red-800",
+            Some(Availability::Available) => "icon-[heroicons--check-circle] w-6 h-6 text-green-800",
+            Some(Availability::Taken) => "icon-[heroicons--x-circle] w-6 h-6 text-red-800",
+            Some(Availability::Similar(_)) => "icon-[heroicons--exclamation-triangle] w-6 h-6 text-amber-600",
             None => "icon-[heroicons--x-circle] w-6 h-6 text-black/10"
         },
     }
     });
+    let title = move || match available() {
+        Some(Availability::Similar(name)) => format!("Blocked by similarity to {}", name),
+        _ => "".to_string(),
+    };
 
     view! {
         <div class="rounded-full px-2 py-1 border flex items-center gap-1">
@@ -26,8 +48,39 @@ pub fn Badge<L: Fn() -> bool + 'static, A: Fn() -> Option<bool> + 'static>(
                 <span>{label}</span>
             </p>
             <div class="w-6 h-6 flex">
-                <span class=status></span>
+                <span class=status title=title></span>
             </div>
         </div>
     }
 }
+
+/// Renders a compact availability indicator for a single source
+#[component]
+pub fn StatusDot(
+    source: Sources,
+    #[prop(into)] available: MaybeSignal<Option<Availability>>,
+) -> impl IntoView {
+    let status = available.clone();
+    let color = move || match status() {
+        Some(Availability::Available) => "bg-green-700",
+        Some(Availability::Taken) => "bg-red-700",
+        Some(Availability::Similar(_)) => "bg-amber-500",
+        None => "bg-black/10",
+    };
+    let title = move || {
+        let status = match available() {
+            Some(Availability::Available) => "available".to_string(),
+            Some(Availability::Taken) => "taken".to_string(),
+            Some(Availability::Similar(name)) => format!("too similar to {}", name),
+            None => "unknown".to_string(),
+        };
+        format!("{} {}: {}", source.icon_name(), source.label(), status)
+    };
+
+    view! {
+        <span class="flex items-center" title=title>
+            <span class=format!("w-4 h-4 {}", source.icon())></span>
+            <span class=move || format!("w-2 h-2 rounded-full {}", color())></span>
+        </span>
+    }
+}
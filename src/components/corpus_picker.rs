@@ -0,0 +1,69 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::corpus::CorpusList;
+
+/// Corpora the server has definitions for, and the one it shows by default
+#[server(ListCorpora, "/api")]
+pub async fn list_corpora() -> Result<CorpusList, ServerFnError> {
+    use crate::corpus::{corpora, default_corpus, CorpusInfo};
+
+    Ok(CorpusList {
+        default: default_corpus().to_string(),
+        corpora: corpora()
+            .iter()
+            .map(|corpus| CorpusInfo::from(corpus.as_ref()))
+            .collect(),
+    })
+}
+
+/// Renders a select switching the corpus shown in the names grid, keeping the query string.
+/// An empty `corpus` is the server's default
+#[component]
+pub fn CorpusPicker(#[prop(into)] corpus: Signal<String>) -> impl IntoView {
+    use leptos::ev::Event;
+
+    let options = create_resource(|| (), |_| list_corpora());
+    let location = use_location();
+    let navigate = use_navigate();
+    let handle_change = move |ev: Event| {
+        let query = location.query.get().to_query_string();
+        navigate(
+            &format!("/corpus/{}{}", event_target_value(&ev), query),
+            Default::default(),
+        );
+    };
+
+    view! {
+        <label class="flex items-center gap-2 px-6 py-2 text-sm">
+            "Corpus"
+            <select class="px-1 py-1 border" on:change=handle_change>
+                <Transition fallback=|| ()>
+                    {move || {
+                        let Some(Ok(list)) = options.get() else {
+                            return ().into_view();
+                        };
+                        list.corpora
+                            .into_iter()
+                            .map(|option| {
+                                let id = option.id.clone();
+                                let default = list.default.clone();
+                                let selected = move || {
+                                    corpus.with(|corpus| match corpus.is_empty() {
+                                        true => id == default,
+                                        false => *corpus == id,
+                                    })
+                                };
+                                view! {
+                                    <option value=option.id selected=selected>
+                                        {option.title}
+                                    </option>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </Transition>
+            </select>
+        </label>
+    }
+}
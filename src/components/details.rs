@@ -0,0 +1,303 @@
+use cfg_if::cfg_if;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::Sources;
+
+/// Metadata about whoever currently holds a taken name
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Default)]
+pub struct NameDetails {
+    pub link: String,
+    pub owner: Option<String>,
+    pub description: Option<String>,
+    pub latest_version: Option<String>,
+    pub last_publish: Option<String>,
+    pub last_activity: Option<String>,
+    pub weekly_downloads: Option<u64>,
+    pub recent_downloads: Option<u64>,
+    pub stars: Option<u64>,
+    pub followers: Option<u64>,
+    pub archived: Option<bool>,
+}
+
+impl NameDetails {
+    /// Labelled rows for every known field
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        let date = |date: &String| date.chars().take(10).collect::<String>();
+        let mut rows = vec![];
+        if let Some(owner) = &self.owner {
+            rows.push(("owner", owner.clone()));
+        }
+        if let Some(description) = &self.description {
+            rows.push(("description", description.clone()));
+        }
+        if let Some(version) = &self.latest_version {
+            rows.push(("latest", version.clone()));
+        }
+        if let Some(published) = &self.last_publish {
+            rows.push(("published", date(published)));
+        }
+        if let Some(activity) = &self.last_activity {
+            rows.push(("last active", date(activity)));
+        }
+        if let Some(downloads) = self.weekly_downloads {
+            rows.push(("weekly downloads", downloads.to_string()));
+        }
+        if let Some(downloads) = self.recent_downloads {
+            rows.push(("90 day downloads", downloads.to_string()));
+        }
+        if let Some(stars) = self.stars {
+            rows.push(("stars", stars.to_string()));
+        }
+        if let Some(followers) = self.followers {
+            rows.push(("followers", followers.to_string()));
+        }
+        if let Some(true) = self.archived {
+            rows.push(("archived", "yes".to_string()));
+        }
+        rows
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use percent_encoding::utf8_percent_encode;
+        use std::collections::HashMap;
+        use crate::components::{fetch_json, github_client};
+        use nameit_core::check::{repository_parts, PATH_SEGMENT};
+
+        async fn npm_details(client: &reqwest::Client, name: &str) -> Result<Option<NameDetails>, ServerFnError> {
+            #[derive(Deserialize)]
+            struct Maintainer {
+                name: String,
+            }
+
+            #[derive(Deserialize)]
+            struct Package {
+                description: Option<String>,
+                #[serde(rename = "dist-tags", default)]
+                dist_tags: HashMap<String, String>,
+                #[serde(default)]
+                time: HashMap<String, String>,
+                #[serde(default)]
+                maintainers: Vec<Maintainer>,
+            }
+
+            #[derive(Deserialize)]
+            struct Downloads {
+                downloads: u64,
+            }
+
+            let name = utf8_percent_encode(name, PATH_SEGMENT).to_string();
+            let Some(package) = fetch_json::<Package>(client, format!("https://registry.npmjs.org/{}", name)).await? else {
+                return Ok(None);
+            };
+            let downloads = fetch_json::<Downloads>(
+                client,
+                format!("https://api.npmjs.org/downloads/point/last-week/{}", name),
+            )
+            .await
+            .ok()
+            .flatten();
+            let latest_version = package.dist_tags.get("latest").cloned();
+
+            Ok(Some(NameDetails {
+                link: format!("https://www.npmjs.com/package/{}", name),
+                owner: package.maintainers.first().map(|m| m.name.clone()),
+                description: package.description,
+                last_publish: latest_version
+                    .as_ref()
+                    .and_then(|version| package.time.get(version).cloned()),
+                last_activity: package.time.get("modified").cloned(),
+                latest_version,
+                weekly_downloads: downloads.map(|d| d.downloads),
+                ..Default::default()
+            }))
+        }
+
+        async fn crate_details(client: &reqwest::Client, name: &str) -> Result<Option<NameDetails>, ServerFnError> {
+            #[derive(Deserialize)]
+            struct Crate {
+                description: Option<String>,
+                max_version: Option<String>,
+                updated_at: Option<String>,
+                recent_downloads: Option<u64>,
+            }
+
+            #[derive(Deserialize)]
+            struct CrateResponse {
+                #[serde(rename = "crate")]
+                krate: Crate,
+            }
+
+            #[derive(Deserialize)]
+            struct Owner {
+                login: String,
+            }
+
+            #[derive(Deserialize)]
+            struct Owners {
+                users: Vec<Owner>,
+            }
+
+            let name = utf8_percent_encode(name, PATH_SEGMENT).to_string();
+            let url = format!("https://crates.io/api/v1/crates/{}", name);
+            let Some(response) = fetch_json::<CrateResponse>(client, url.clone()).await? else {
+                return Ok(None);
+            };
+            let owners = fetch_json::<Owners>(client, format!("{}/owners", url))
+                .await
+                .ok()
+                .flatten();
+            let krate = response.krate;
+
+            Ok(Some(NameDetails {
+                link: format!("https://crates.io/crates/{}", name),
+                owner: owners.and_then(|o| o.users.into_iter().next()).map(|o| o.login),
+                description: krate.description,
+                latest_version: krate.max_version,
+                last_publish: krate.updated_at,
+                recent_downloads: krate.recent_downloads,
+                ..Default::default()
+            }))
+        }
+
+        async fn github_account_details(client: &reqwest::Client, login: &str) -> Result<Option<NameDetails>, ServerFnError> {
+            #[derive(Deserialize)]
+            struct Account {
+                login: String,
+                html_url: String,
+                bio: Option<String>,
+                followers: Option<u64>,
+                updated_at: Option<String>,
+            }
+
+            let url = format!("https://api.github.com/users/{}", utf8_percent_encode(login, PATH_SEGMENT));
+            let account = fetch_json::<Account>(client, url).await?;
+            Ok(account.map(|account| NameDetails {
+                link: account.html_url,
+                owner: Some(account.login),
+                description: account.bio,
+                last_activity: account.updated_at,
+                followers: account.followers,
+                ..Default::default()
+            }))
+        }
+
+        async fn github_repo_details(client: &reqwest::Client, full_name: &str) -> Result<Option<NameDetails>, ServerFnError> {
+            #[derive(Deserialize)]
+            struct Owner {
+                login: String,
+            }
+
+            #[derive(Deserialize)]
+            struct Repository {
+                html_url: String,
+                owner: Owner,
+                description: Option<String>,
+                stargazers_count: Option<u64>,
+                archived: Option<bool>,
+                pushed_at: Option<String>,
+            }
+
+            let Some((owner, name)) = repository_parts(full_name) else {
+                return Ok(None);
+            };
+            let url = format!(
+                "https://api.github.com/repos/{}/{}",
+                utf8_percent_encode(owner, PATH_SEGMENT),
+                utf8_percent_encode(name, PATH_SEGMENT)
+            );
+            let repository = fetch_json::<Repository>(client, url).await?;
+            Ok(repository.map(|repository| NameDetails {
+                link: repository.html_url,
+                owner: Some(repository.owner.login),
+                description: repository.description,
+                last_activity: repository.pushed_at,
+                stars: repository.stargazers_count,
+                archived: repository.archived,
+                ..Default::default()
+            }))
+        }
+    }
+}
+
+/// Fetches metadata about the current holder of `title` on `source`
+#[server(TakenDetails, "/api")]
+pub async fn taken_details(
+    source: Sources,
+    title: String,
+) -> Result<Option<NameDetails>, ServerFnError> {
+    use crate::state::AppCache;
+
+    let cache = use_context::<AppCache>().expect("Missing context provider");
+    let title = title.to_lowercase();
+    let key = format!("details-{:?}-{}", source, title);
+    if let Some(cached) = cache.get_json::<Option<NameDetails>>(key.clone()) {
+        return Ok(cached);
+    }
+
+    let client = use_context::<reqwest::Client>().expect("Missing context provider");
+    let details = match source {
+        Sources::PackageNpm => npm_details(&client, &title).await?,
+        Sources::Crates => crate_details(&client, &title).await?,
+        Sources::Github => github_account_details(&github_client()?, &title).await?,
+        Sources::GithubRepo => github_repo_details(&github_client()?, &title).await?,
+        Sources::OrgNpm => None,
+    };
+    cache.set_json(key, &details);
+    Ok(details)
+}
+
+/// Renders an expandable panel describing the holder of a taken name
+#[component]
+pub fn HolderDetails(source: Sources, #[prop(into)] query: Signal<String>) -> impl IntoView {
+    let (open, set_open) = create_signal(false);
+
+    let details = create_resource(
+        move || open().then(query),
+        move |query| async move {
+            match query {
+                Some(query) => taken_details(source, query).await,
+                None => Ok(None),
+            }
+        },
+    );
+
+    let panel = move || match details.get() {
+        Some(Ok(Some(details))) => view! {
+            <dl class="grid grid-cols-[auto_1fr] gap-x-2 max-w-xs">
+                {details
+                    .rows()
+                    .into_iter()
+                    .map(|(label, value)| {
+                        view! {
+                            <dt class="text-black/50">{label}</dt>
+                            <dd class="break-words">{value}</dd>
+                        }
+                    })
+                    .collect_view()}
+            </dl>
+            <a class="underline" href=details.link target="_blank">
+                "View registry page"
+            </a>
+        }
+        .into_view(),
+        Some(Ok(None)) => view! { <p class="text-black/50">"No details available"</p> }.into_view(),
+        Some(Err(e)) => view! { <p class="text-red-800">{e.to_string()}</p> }.into_view(),
+        None => ().into_view(),
+    };
+
+    view! {
+        <div class="text-xs px-2">
+            <button class="text-black/50 underline" on:click=move |_| set_open.update(|open| *open = !*open)>
+                {move || if open() { "Hide details" } else { "Details" }}
+            </button>
+            <Show when=open>
+                <Suspense fallback=|| view! { <p class="text-black/50">"Loading"</p> }>
+                    {panel}
+                </Suspense>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,274 @@
+use leptos::*;
+use leptos_router::*;
+use std::{fmt, str::FromStr};
+
+use crate::components::{
+    Availability, AvailabilityMatrix, Entry, SourceIcon, Sources, MATRIX_SOURCES,
+};
+
+/// Order of the names grid, corpus order when unset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NameSort {
+    Alphabet,
+    Length,
+    /// Most free sources first
+    Free,
+}
+
+impl NameSort {
+    pub const ALL: [NameSort; 3] = [NameSort::Alphabet, NameSort::Length, NameSort::Free];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NameSort::Alphabet => "Alphabet",
+            NameSort::Length => "Length",
+            NameSort::Free => "Free sources",
+        }
+    }
+}
+
+impl fmt::Display for NameSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NameSort::Alphabet => "alpha",
+            NameSort::Length => "length",
+            NameSort::Free => "free",
+        })
+    }
+}
+
+impl FromStr for NameSort {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        NameSort::ALL
+            .into_iter()
+            .find(|sort| sort.to_string() == value)
+            .ok_or(())
+    }
+}
+
+/// Filters and order of the names grid, kept in the URL query next to `q` so views can be
+/// shared
+#[derive(Clone, Copy)]
+pub struct NameFilters {
+    /// Comma separated keys of the sources an entry has to be free on
+    free: Memo<Option<String>>,
+    set_free: SignalSetter<Option<String>>,
+    max_length: Memo<Option<usize>>,
+    set_max_length: SignalSetter<Option<usize>>,
+    letter: Memo<Option<char>>,
+    set_letter: SignalSetter<Option<char>>,
+    /// Title of a flag the entry has to carry
+    flag: Memo<Option<String>>,
+    set_flag: SignalSetter<Option<String>>,
+    sort: Memo<Option<NameSort>>,
+    set_sort: SignalSetter<Option<NameSort>>,
+}
+
+impl NameFilters {
+    pub fn from_query() -> Self {
+        let (free, set_free) = create_query_signal::<String>("free");
+        let (max_length, set_max_length) = create_query_signal::<usize>("max");
+        let (letter, set_letter) = create_query_signal::<char>("letter");
+        let (flag, set_flag) = create_query_signal::<String>("flag");
+        let (sort, set_sort) = create_query_signal::<NameSort>("sort");
+        NameFilters {
+            free,
+            set_free,
+            max_length,
+            set_max_length,
+            letter,
+            set_letter,
+            flag,
+            set_flag,
+            sort,
+            set_sort,
+        }
+    }
+
+    pub fn free_sources(&self) -> Vec<Sources> {
+        self.free
+            .get()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(Sources::from_key)
+            .collect()
+    }
+
+    fn toggle_free(&self, source: Sources) {
+        let mut sources = self.free_sources();
+        match sources.iter().position(|s| *s == source) {
+            Some(index) => {
+                sources.remove(index);
+            }
+            None => sources.push(source),
+        }
+        let keys: Vec<_> = sources.iter().map(|source| source.key()).collect();
+        (self.set_free)((!keys.is_empty()).then(|| keys.join(",")));
+    }
+
+    /// Whether filtering or sorting needs the availability of every entry
+    pub fn needs_availability(&self) -> bool {
+        !self.free_sources().is_empty() || self.sort.get() == Some(NameSort::Free)
+    }
+
+    pub fn apply(&self, entries: Vec<Entry>, matrix: AvailabilityMatrix) -> Vec<Entry> {
+        let free = self.free_sources();
+        let max_length = self.max_length.get();
+        let letter = self.letter.get().map(|letter| letter.to_ascii_lowercase());
+        let flag = self.flag.get();
+
+        let mut entries: Vec<Entry> = entries
+            .into_iter()
+            .filter(|entry| max_length.is_none_or(|max| entry.title.chars().count() <= max))
+            .filter(|entry| {
+                letter.is_none_or(|letter| {
+                    entry
+                        .title
+                        .chars()
+                        .find(|c| c.is_alphanumeric())
+                        .is_some_and(|c| c.to_ascii_lowercase() == letter)
+                })
+            })
+            .filter(|entry| {
+                flag.as_ref()
+                    .is_none_or(|flag| entry.flags.iter().any(|f| f.title == *flag))
+            })
+            .filter(|entry| {
+                free.iter().all(|source| {
+                    matrix.result(&entry.title, *source) == Some(Availability::Available)
+                })
+            })
+            .collect();
+
+        match self.sort.get() {
+            Some(NameSort::Alphabet) => entries.sort_by_key(|entry| entry.title.to_lowercase()),
+            Some(NameSort::Length) => entries.sort_by_key(|entry| entry.title.chars().count()),
+            Some(NameSort::Free) => {
+                entries.sort_by_key(|entry| std::cmp::Reverse(matrix.free(&entry.title)))
+            }
+            None => (),
+        }
+        entries
+    }
+}
+
+/// Renders the controls of the names grid filters
+#[component]
+pub fn NameFilterBar(
+    filters: NameFilters,
+    /// Flag titles found in the corpus
+    #[prop(into)]
+    flags: Signal<Vec<String>>,
+) -> impl IntoView {
+    use leptos::ev::Event;
+
+    let set_max_length = move |ev: Event| {
+        (filters.set_max_length)(event_target_value(&ev).trim().parse::<usize>().ok())
+    };
+    let set_letter = move |ev: Event| (filters.set_letter)(event_target_value(&ev).chars().next());
+    let set_flag = move |ev: Event| {
+        let flag = event_target_value(&ev);
+        (filters.set_flag)((!flag.is_empty()).then_some(flag))
+    };
+    let set_sort = move |ev: Event| (filters.set_sort)(event_target_value(&ev).parse().ok());
+
+    view! {
+        <div class="flex flex-wrap items-center gap-3 px-6 py-2 text-sm">
+            <span class="flex items-center gap-2">
+                "Free on"
+                {MATRIX_SOURCES
+                    .into_iter()
+                    .map(|source| {
+                        view! {
+                            <label class="flex items-center gap-1">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || filters.free_sources().contains(&source)
+                                    on:change=move |_| filters.toggle_free(source)
+                                />
+                                <span class=format!("w-4 h-4 {}", source.icon())></span>
+                                {source.label()}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
+            </span>
+            <label class="flex items-center gap-1">
+                "Max length"
+                <input
+                    type="number"
+                    min="1"
+                    class="px-1 border w-16"
+                    prop:value=move || filters.max_length.get().map(|max| max.to_string())
+                    on:change=set_max_length
+                />
+            </label>
+            <label class="flex items-center gap-1">
+                "Starts with"
+                <select class="px-1 py-1 border" on:change=set_letter>
+                    <option value="">"Any"</option>
+                    {('A'..='Z')
+                        .map(|letter| {
+                            view! {
+                                <option
+                                    value=letter.to_string()
+                                    selected=move || {
+                                        filters.letter.get().map(|c| c.to_ascii_uppercase())
+                                            == Some(letter)
+                                    }
+                                >
+                                    {letter.to_string()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </label>
+            <Show when=move || flags.with(|flags| !flags.is_empty())>
+                <label class="flex items-center gap-1">
+                    "Country"
+                    <select class="px-1 py-1 border" on:change=set_flag>
+                        <option value="">"Any"</option>
+                        {move || {
+                            flags()
+                                .into_iter()
+                                .map(|flag| {
+                                    let value = flag.clone();
+                                    view! {
+                                        <option
+                                            value=flag.clone()
+                                            selected=move || filters.flag.get() == Some(value.clone())
+                                        >
+                                            {flag}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </select>
+                </label>
+            </Show>
+            <label class="flex items-center gap-1">
+                "Sort"
+                <select class="px-1 py-1 border" on:change=set_sort>
+                    <option value="">"Corpus order"</option>
+                    {NameSort::ALL
+                        .into_iter()
+                        .map(|sort| {
+                            view! {
+                                <option
+                                    value=sort.to_string()
+                                    selected=move || filters.sort.get() == Some(sort)
+                                >
+                                    {sort.label()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </label>
+        </div>
+    }
+}
@@ -0,0 +1,322 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{list_corpora, Entry, NameGrid};
+use crate::generator::{
+    markov::{MarkovCorpus, MarkovOptions},
+    GeneratorOptions, WordList,
+};
+
+/// Candidates produced by one generator run
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct Generated {
+    pub seed: u64,
+    pub entries: Vec<Entry>,
+}
+
+impl Generated {
+    pub fn new(seed: u64, names: Vec<String>) -> Self {
+        Generated {
+            seed,
+            entries: names
+                .into_iter()
+                .map(|title| Entry {
+                    link: "".to_string(),
+                    title,
+                    image: None,
+                    flags: vec![],
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Upper bound on candidates produced by a single request
+#[cfg(feature = "ssr")]
+const MAX_GENERATED: usize = 200;
+
+/// Upper bound on word lists combined into one name
+#[cfg(feature = "ssr")]
+const MAX_PATTERN: usize = 4;
+
+#[server(GenerateNames, "/api")]
+pub async fn generate_names(options: GeneratorOptions) -> Result<Generated, ServerFnError> {
+    let mut options = GeneratorOptions {
+        count: options.count.min(MAX_GENERATED),
+        ..options
+    };
+    options.pattern.truncate(MAX_PATTERN);
+    let (seed, names) = crate::generator::generate(&options);
+    Ok(Generated::new(seed, names))
+}
+
+/// Synthesizes pronounceable names from a Markov model trained on the chosen corpus
+#[server(SynthesizeNames, "/api")]
+pub async fn synthesize_names(options: MarkovOptions) -> Result<Generated, ServerFnError> {
+    use crate::components::corpus_names;
+    use crate::generator::markov::{corpus_words, Markov};
+    use std::collections::HashSet;
+
+    let options = MarkovOptions {
+        count: options.count.min(MAX_GENERATED),
+        order: options.order.clamp(1, 5),
+        ..options
+    };
+    let dictionary: HashSet<String> = WordList::ALL
+        .iter()
+        .flat_map(|list| list.words())
+        .map(String::from)
+        .collect();
+    let words: Vec<String> = match options.corpus.clone() {
+        MarkovCorpus::Corpus(id) => corpus_names(id)
+            .await?
+            .iter()
+            .flat_map(|entry| corpus_words(&entry.title))
+            .collect(),
+        MarkovCorpus::WordLists => dictionary.iter().cloned().collect(),
+    };
+
+    let model = Markov::train(options.order, words);
+    let (seed, names) = model.generate(&options, &dictionary);
+    Ok(Generated::new(seed, names))
+}
+
+/// A generator run started from the page
+#[derive(Clone, Debug, PartialEq)]
+enum GenerateRequest {
+    Words(GeneratorOptions),
+    Markov(MarkovOptions),
+}
+
+const PATTERNS: [&[WordList]; 5] = [
+    &[WordList::Adjectives, WordList::Nouns],
+    &[WordList::Verbs, WordList::Nouns],
+    &[WordList::Adjectives, WordList::Themes],
+    &[WordList::Themes, WordList::Nouns],
+    &[WordList::Nouns, WordList::Verbs],
+];
+
+fn pattern_label(pattern: &[WordList]) -> String {
+    pattern
+        .iter()
+        .map(|list| list.label())
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Renders generator settings and fills a name grid with the results
+#[component]
+pub fn Generator(set_query_value: SignalSetter<Option<String>>) -> impl IntoView {
+    use leptos::ev::Event;
+
+    let (markov, set_markov) = create_signal(false);
+    let options = create_rw_signal(GeneratorOptions::default());
+    let corpora = create_resource(|| (), |_| list_corpora());
+    let markov_options = create_rw_signal(MarkovOptions::default());
+    let generate = create_action(|request: &GenerateRequest| {
+        let request = request.clone();
+        async move {
+            match request {
+                GenerateRequest::Words(options) => generate_names(options).await,
+                GenerateRequest::Markov(options) => synthesize_names(options).await,
+            }
+        }
+    });
+
+    let entries = move || match generate.value().get() {
+        Some(Ok(generated)) => generated.entries,
+        _ => vec![],
+    };
+    let seed = move || match generate.value().get() {
+        Some(Ok(generated)) => Some(generated.seed),
+        _ => None,
+    };
+
+    let parse = |ev: &Event| event_target_value(ev).trim().parse::<usize>().ok();
+    let set_pattern = move |ev: Event| {
+        let index = event_target_value(&ev).parse::<usize>().unwrap_or(0);
+        let pattern = PATTERNS.get(index).copied().unwrap_or(PATTERNS[0]);
+        options.update(|o| o.pattern = pattern.to_vec());
+    };
+    let set_seed = move |ev: Event| {
+        let seed = event_target_value(&ev).trim().parse::<u64>().ok();
+        options.update(|o| o.seed = seed);
+        markov_options.update(|o| o.seed = seed);
+    };
+    let set_max_length = move |ev: Event| options.update(|o| o.constraints.max_length = parse(&ev));
+    let set_max_syllables =
+        move |ev: Event| options.update(|o| o.constraints.max_syllables = parse(&ev));
+    let set_corpus = move |ev: Event| {
+        let corpus = match event_target_value(&ev) {
+            id if id.is_empty() => MarkovCorpus::WordLists,
+            id => MarkovCorpus::Corpus(id),
+        };
+        markov_options.update(|o| o.corpus = corpus);
+    };
+    let set_order =
+        move |ev: Event| markov_options.update(|o| o.order = parse(&ev).unwrap_or(o.order));
+    let set_min_length = move |ev: Event| {
+        markov_options.update(|o| o.min_length = parse(&ev).unwrap_or(o.min_length))
+    };
+    let set_markov_max_length = move |ev: Event| {
+        markov_options.update(|o| o.max_length = parse(&ev).unwrap_or(o.max_length))
+    };
+    let dispatch = move |_| {
+        generate.dispatch(match markov() {
+            true => GenerateRequest::Markov(markov_options.get()),
+            false => GenerateRequest::Words(options.get()),
+        })
+    };
+
+    view! {
+        <div class="px-6 py-3 flex flex-wrap items-end gap-3 text-sm">
+            <label class="flex flex-col">
+                "Generator"
+                <select
+                    class="px-1 py-1 border"
+                    on:change=move |ev| set_markov(event_target_value(&ev) == "markov")
+                >
+                    <option value="words">"Word lists"</option>
+                    <option value="markov">"Pronounceable"</option>
+                </select>
+            </label>
+            <Show
+                when=markov
+                fallback=move || {
+                    view! {
+                        <label class="flex flex-col">
+                            "Pattern"
+                            <select class="px-1 py-1 border" on:change=set_pattern>
+                                {PATTERNS
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(index, pattern)| {
+                                        view! {
+                                            <option value=index.to_string()>{pattern_label(pattern)}</option>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </select>
+                        </label>
+                        <label class="flex flex-col">
+                            "Max length"
+                            <input
+                                type="number"
+                                min="1"
+                                class="px-1 py-1 border w-20"
+                                value=move || {
+                                    options.with(|o| o.constraints.max_length.map(|v| v.to_string()))
+                                }
+                                on:change=set_max_length
+                            />
+                        </label>
+                        <label class="flex flex-col">
+                            "Max syllables"
+                            <input
+                                type="number"
+                                min="1"
+                                class="px-1 py-1 border w-20"
+                                value=move || {
+                                    options
+                                        .with(|o| o.constraints.max_syllables.map(|v| v.to_string()))
+                                }
+                                on:change=set_max_syllables
+                            />
+                        </label>
+                        <label class="flex items-center gap-1">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || options.with(|o| o.constraints.starts_with_letter)
+                                on:change=move |_| {
+                                    options
+                                        .update(|o| {
+                                            o.constraints.starts_with_letter = !o.constraints.starts_with_letter
+                                        })
+                                }
+                            />
+                            "Starts with a letter"
+                        </label>
+                        <label class="flex items-center gap-1">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || options.with(|o| o.constraints.no_hyphens)
+                                on:change=move |_| {
+                                    options.update(|o| o.constraints.no_hyphens = !o.constraints.no_hyphens)
+                                }
+                            />
+                            "No hyphens"
+                        </label>
+                    }
+                }
+            >
+                <label class="flex flex-col">
+                    "Corpus"
+                    <select class="px-1 py-1 border" on:change=set_corpus>
+                        <Transition fallback=|| ()>
+                            {move || {
+                                corpora
+                                    .get()
+                                    .and_then(|corpora| corpora.ok())
+                                    .map(|list| list.corpora)
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|corpus| {
+                                        view! { <option value=corpus.id>{corpus.title}</option> }
+                                    })
+                                    .collect_view()
+                            }}
+                        </Transition>
+                        <option value="">"Word lists"</option>
+                    </select>
+                </label>
+                <label class="flex flex-col">
+                    "Order"
+                    <input
+                        type="number"
+                        min="1"
+                        max="5"
+                        class="px-1 py-1 border w-16"
+                        value=move || markov_options.with(|o| o.order.to_string())
+                        on:change=set_order
+                    />
+                </label>
+                <label class="flex flex-col">
+                    "Min length"
+                    <input
+                        type="number"
+                        min="1"
+                        class="px-1 py-1 border w-20"
+                        value=move || markov_options.with(|o| o.min_length.to_string())
+                        on:change=set_min_length
+                    />
+                </label>
+                <label class="flex flex-col">
+                    "Max length"
+                    <input
+                        type="number"
+                        min="1"
+                        class="px-1 py-1 border w-20"
+                        value=move || markov_options.with(|o| o.max_length.to_string())
+                        on:change=set_markov_max_length
+                    />
+                </label>
+            </Show>
+            <label class="flex flex-col">
+                "Seed"
+                <input
+                    type="text"
+                    class="px-1 py-1 border w-32"
+                    placeholder="random"
+                    on:change=set_seed
+                />
+            </label>
+            <button class="px-3 py-1 border bg-black text-white" on:click=dispatch>
+                "Generate"
+            </button>
+            <Show when=move || seed().is_some()>
+                <span class="text-black/50">"Seed " {move || seed().map(|s| s.to_string())}</span>
+            </Show>
+        </div>
+        <NameGrid entries set_query_value/>
+    }
+}
@@ -0,0 +1,227 @@
+use cfg_if::cfg_if;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Availability, Badge, HolderDetails, Sources};
+use crate::signals::debounce_signal;
+
+/// An existing GitHub entity holding the checked name
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Hash, Eq)]
+pub enum GithubMatch {
+    User(String),
+    Organization(String),
+    Repository(String),
+}
+
+impl GithubMatch {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GithubMatch::User(_) => "user",
+            GithubMatch::Organization(_) => "org",
+            GithubMatch::Repository(_) => "repo",
+        }
+    }
+}
+
+/// Splits a comma separated owner list into trimmed lowercase logins
+pub fn parse_owners(owners: &str) -> Vec<String> {
+    owners
+        .split(',')
+        .map(|owner| owner.trim().to_lowercase())
+        .filter(|owner| !owner.is_empty())
+        .collect()
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use percent_encoding::utf8_percent_encode;
+        use std::sync::OnceLock;
+
+        use crate::components::{fetch_json, USER_AGENT};
+        use crate::state::AppCache;
+        use nameit_core::check::PATH_SEGMENT;
+
+        /// Client for the GitHub API, authenticated with `GITHUB_TOKEN` when it is set
+        fn build_github_client() -> Result<reqwest::Client, String> {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(
+                reqwest::header::ACCEPT,
+                reqwest::header::HeaderValue::from_static("application/vnd.github+json"),
+            );
+            if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+                let value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+                    .map_err(|e| e.to_string())?;
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+            reqwest::Client::builder()
+                .user_agent(USER_AGENT)
+                .default_headers(headers)
+                .build()
+                .map_err(|e| e.to_string())
+        }
+
+        /// The GitHub API client, built once so every lookup shares its connections
+        pub(crate) fn github_client() -> Result<reqwest::Client, ServerFnError> {
+            static CLIENT: OnceLock<Result<reqwest::Client, String>> = OnceLock::new();
+            CLIENT
+                .get_or_init(build_github_client)
+                .clone()
+                .map_err(ServerFnError::ServerError)
+        }
+
+        async fn lookup_account(
+            cache: &AppCache,
+            client: &reqwest::Client,
+            login: &str,
+        ) -> Result<Option<GithubMatch>, ServerFnError> {
+            #[derive(Deserialize)]
+            struct Account {
+                login: String,
+                #[serde(rename = "type")]
+                kind: String,
+            }
+
+            let url = format!(
+                "https://api.github.com/users/{}",
+                utf8_percent_encode(login, PATH_SEGMENT)
+            );
+            if let Some(cached) = cache.get_json::<Option<GithubMatch>>(url.clone()) {
+                return Ok(cached);
+            }
+            let result = fetch_json::<Account>(client, url.clone())
+                .await?
+                .map(|account| match account.kind.as_str() {
+                    "Organization" => GithubMatch::Organization(account.login),
+                    _ => GithubMatch::User(account.login),
+                });
+            cache.set_json(url, &result);
+            Ok(result)
+        }
+
+        async fn lookup_repository(
+            cache: &AppCache,
+            client: &reqwest::Client,
+            owner: &str,
+            name: &str,
+        ) -> Result<Option<GithubMatch>, ServerFnError> {
+            #[derive(Deserialize)]
+            struct Repository {
+                full_name: String,
+            }
+
+            let url = format!(
+                "https://api.github.com/repos/{}/{}",
+                utf8_percent_encode(owner, PATH_SEGMENT),
+                utf8_percent_encode(name, PATH_SEGMENT)
+            );
+            if let Some(cached) = cache.get_json::<Option<GithubMatch>>(url.clone()) {
+                return Ok(cached);
+            }
+            let result = fetch_json::<Repository>(client, url.clone())
+                .await?
+                .map(|repository| GithubMatch::Repository(repository.full_name));
+            cache.set_json(url, &result);
+            Ok(result)
+        }
+    }
+}
+
+/// Looks up the account named `title` and the `title` repository under each owner
+#[server(CheckGithub, "/api")]
+pub async fn check_github(
+    title: String,
+    #[server(default)] owners: Vec<String>,
+) -> Result<Vec<GithubMatch>, ServerFnError> {
+    let cache = use_context::<AppCache>().expect("Missing context provider");
+    let client = github_client()?;
+    let title = title.to_lowercase();
+
+    let mut matches = vec![];
+    if let Some(account) = lookup_account(&cache, &client, &title).await? {
+        matches.push(account);
+    }
+    for owner in owners {
+        if let Some(repository) = lookup_repository(&cache, &client, &owner, &title).await? {
+            matches.push(repository);
+        }
+    }
+    Ok(matches)
+}
+
+/// Renders GitHub account availability and repository availability under each owner
+#[component]
+pub fn GithubAvailable(query: Memo<String>, owners: Memo<Vec<String>>) -> impl IntoView {
+    let debounced = debounce_signal(std::time::Duration::from_millis(300), query);
+
+    let matches = create_resource(
+        move || (debounced(), owners()),
+        |(query, owners)| check_github(query, owners),
+    );
+
+    let found = move || match matches.get() {
+        Some(Ok(matches)) => Some(matches),
+        _ => None,
+    };
+
+    let account = create_memo(move |_| {
+        found().map(|matches| {
+            matches
+                .into_iter()
+                .find(|m| !matches!(m, GithubMatch::Repository(_)))
+        })
+    });
+
+    let label = Signal::derive(move || {
+        account()
+            .flatten()
+            .map(|m| m.label().to_string())
+            .unwrap_or_default()
+    });
+
+    view! {
+        <div class="flex flex-col gap-1">
+            <Badge
+                icon="icon-[devicon--github]".to_string()
+                label=label
+                loading=matches.loading()
+                available=move || account().map(|m| Availability::from_available(m.is_none()))
+            />
+            <Show when=move || account().flatten().is_some()>
+                <HolderDetails source=Sources::Github query=debounced/>
+            </Show>
+        </div>
+        <For
+            each=owners
+            key=|owner| owner.clone()
+            children=move |owner| {
+                let label = format!("{}/", owner);
+                let full_name = create_memo(move |_| {
+                    format!("{}/{}", owner, debounced().to_lowercase())
+                });
+                let available = move || {
+                    found()
+                        .map(|matches| {
+                            !matches.into_iter().any(|m| match m {
+                                GithubMatch::Repository(name) => name.to_lowercase() == full_name(),
+                                _ => false,
+                            })
+                        })
+                        .map(Availability::from_available)
+                };
+                view! {
+                    <div class="flex flex-col gap-1">
+                        <Badge
+                            icon="icon-[devicon--github]".to_string()
+                            label=label
+                            loading=matches.loading()
+                            available=available
+                        />
+                        <Show when=move || available() == Some(Availability::Taken)>
+                            <HolderDetails source=Sources::GithubRepo query=full_name/>
+                        </Show>
+                    </div>
+                }
+            }
+        />
+    }
+}
@@ -0,0 +1,68 @@
+use leptos::*;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+
+use crate::badge::source_label;
+use crate::components::Sources;
+use crate::history::{display_time, NameHistory};
+
+/// Renders how the results of a name changed over time on each source
+#[component]
+pub fn Timeline(history: NameHistory) -> impl IntoView {
+    let export = format!(
+        "/v1/history/{}",
+        utf8_percent_encode(&history.name, NON_ALPHANUMERIC)
+    );
+    let timeline = match history.sources.is_empty() {
+        true => view! { <p class="text-black/50">"No checks recorded for this name yet"</p> }
+            .into_view(),
+        false => history
+            .sources
+            .into_iter()
+            .map(|source| {
+                let label = Sources::from_key(&source.source)
+                    .map_or_else(|| source.source.clone(), source_label);
+                let events = source
+                    .spans
+                    .iter()
+                    .enumerate()
+                    .map(|(index, span)| {
+                        let previous = index.checked_sub(1).map(|index| &source.spans[index]);
+                        view! {
+                            <li>
+                                <span class="font-semibold">{span.describe(previous)}</span>
+                                <span class="text-black/50">
+                                    {format!(
+                                        " {}, last seen {}",
+                                        display_time(span.first_seen),
+                                        display_time(span.last_seen),
+                                    )}
+                                </span>
+                            </li>
+                        }
+                    })
+                    .collect_view();
+                view! {
+                    <section>
+                        <h3 class="font-semibold">{label}</h3>
+                        <ol class="list-disc pl-5">{events}</ol>
+                    </section>
+                }
+            })
+            .collect_view(),
+    };
+
+    view! {
+        <div class="flex flex-col gap-3 text-sm">
+            {timeline}
+            <p class="flex gap-3 text-black/50">
+                "Export"
+                <a class="underline" href=export.clone() rel="external">
+                    "JSON"
+                </a>
+                <a class="underline" href=format!("{}?format=csv", export) rel="external">
+                    "CSV"
+                </a>
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,542 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::components::{Availability, Entry, NameAvailability, SourceIcon, Sources, StatusDot};
+
+/// Sources an imported list can be checked against
+pub const IMPORT_SOURCES: [Sources; 4] = [
+    Sources::PackageNpm,
+    Sources::OrgNpm,
+    Sources::Crates,
+    Sources::Github,
+];
+
+/// Shape of an imported candidate list
+#[derive(Deserialize, Clone, Copy, Debug, Serialize, PartialEq, Default)]
+pub enum ImportFormat {
+    /// Guessed from the content
+    #[default]
+    Auto,
+    /// One candidate per line, `#` starts a comment
+    Text,
+    /// Comma or tab separated rows, the name in the first or `name` column
+    Csv,
+    /// An array of names or of objects with a `title` or `name`
+    Json,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 4] = [
+        ImportFormat::Auto,
+        ImportFormat::Text,
+        ImportFormat::Csv,
+        ImportFormat::Json,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportFormat::Auto => "Auto",
+            ImportFormat::Text => "Text",
+            ImportFormat::Csv => "CSV",
+            ImportFormat::Json => "JSON",
+        }
+    }
+
+    pub fn from_file_name(name: &str) -> Self {
+        match name
+            .rsplit('.')
+            .next()
+            .map(|extension| extension.to_lowercase())
+        {
+            Some(extension) if extension == "csv" || extension == "tsv" => ImportFormat::Csv,
+            Some(extension) if extension == "json" => ImportFormat::Json,
+            Some(extension) if extension == "txt" => ImportFormat::Text,
+            _ => ImportFormat::Auto,
+        }
+    }
+
+    fn detect(text: &str) -> Self {
+        let text = text.trim_start();
+        if text.starts_with('[') {
+            ImportFormat::Json
+        } else if text
+            .lines()
+            .any(|line| line.contains(',') || line.contains('\t'))
+        {
+            ImportFormat::Csv
+        } else {
+            ImportFormat::Text
+        }
+    }
+}
+
+/// Splits a CSV or TSV row, honouring double quoted fields
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' | '\t' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn parse_csv(text: &str) -> Vec<(String, String)> {
+    let mut rows = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(csv_fields)
+        .peekable();
+    let header = rows.peek().cloned().unwrap_or_default();
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|cell| names.contains(&cell.to_lowercase().as_str()))
+    };
+    let name = column(&["name", "title", "candidate"]);
+    let link = column(&["link", "url"]);
+    if name.is_some() {
+        rows.next();
+    }
+    let name = name.unwrap_or(0);
+    rows.filter_map(|row| {
+        let title = row.get(name)?.clone();
+        let link = link
+            .and_then(|link| row.get(link).cloned())
+            .unwrap_or_default();
+        Some((title, link))
+    })
+    .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Candidate {
+    Title(String),
+    Entry {
+        #[serde(alias = "name")]
+        title: String,
+        #[serde(default, alias = "url")]
+        link: String,
+    },
+}
+
+/// Parses a candidate list into entries, deduplicated by lowercase title
+pub fn parse_candidates(text: &str, format: ImportFormat) -> Result<Vec<Entry>, ServerFnError> {
+    let format = match format {
+        ImportFormat::Auto => ImportFormat::detect(text),
+        format => format,
+    };
+    let candidates: Vec<(String, String)> = match format {
+        ImportFormat::Json => serde_json::from_str::<Vec<Candidate>>(text)
+            .map_err(|error| ServerFnError::ServerError(format!("Invalid JSON list: {}", error)))?
+            .into_iter()
+            .map(|candidate| match candidate {
+                Candidate::Title(title) => (title, String::new()),
+                Candidate::Entry { title, link } => (title, link),
+            })
+            .collect(),
+        ImportFormat::Csv => parse_csv(text),
+        _ => text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| (line.to_string(), String::new()))
+            .collect(),
+    };
+
+    let mut seen = HashSet::new();
+    Ok(candidates
+        .into_iter()
+        .map(|(title, link)| (title.trim().to_string(), link))
+        .filter(|(title, _)| !title.is_empty() && seen.insert(title.to_lowercase()))
+        .map(|(title, link)| Entry {
+            link,
+            title,
+            image: None,
+            flags: vec![],
+        })
+        .collect())
+}
+
+/// An imported candidate and its availability
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct ImportedRow {
+    pub entry: Entry,
+    pub availability: NameAvailability,
+}
+
+/// Upper bound on candidates checked from a single list
+#[cfg(feature = "ssr")]
+const MAX_IMPORTED: usize = 200;
+
+/// Parses a candidate list and checks every candidate against `sources`
+#[server(ImportCandidates, "/api")]
+pub async fn import_candidates(
+    text: String,
+    format: ImportFormat,
+    #[server(default)] sources: Vec<Sources>,
+) -> Result<Vec<ImportedRow>, ServerFnError> {
+    use crate::components::{check_many, MAX_CHECK_TITLES};
+
+    let mut entries = parse_candidates(&text, format)?;
+    entries.truncate(MAX_IMPORTED);
+    let titles = entries
+        .iter()
+        .map(|entry| entry.title.clone())
+        .collect::<Vec<_>>();
+    let mut results = vec![];
+    for titles in titles.chunks(MAX_CHECK_TITLES) {
+        results.extend(check_many(sources.clone(), titles.to_vec()).await?);
+    }
+    Ok(entries
+        .into_iter()
+        .zip(results)
+        .map(|(entry, availability)| ImportedRow {
+            entry,
+            availability,
+        })
+        .collect())
+}
+
+/// Column an import table is ordered by
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ImportSort {
+    Name,
+    Free,
+    Source(Sources),
+}
+
+/// Available first, then similar, taken and unchecked
+fn availability_rank(result: Option<Availability>) -> u8 {
+    match result {
+        Some(Availability::Available) => 0,
+        Some(Availability::Similar(_)) => 1,
+        Some(Availability::Taken) => 2,
+        None => 3,
+    }
+}
+
+fn sort_rows(rows: &mut [ImportedRow], sort: ImportSort, descending: bool) {
+    match sort {
+        ImportSort::Name => rows.sort_by_key(|row| row.entry.title.to_lowercase()),
+        ImportSort::Free => rows.sort_by_key(|row| std::cmp::Reverse(row.availability.free())),
+        ImportSort::Source(source) => {
+            rows.sort_by_key(|row| availability_rank(row.availability.result(source)))
+        }
+    }
+    if descending {
+        rows.reverse();
+    }
+}
+
+/// Renders an importer for pasted or uploaded candidate lists and a sortable table of
+/// their availability
+#[component]
+pub fn Importer(set_query_value: SignalSetter<Option<String>>) -> impl IntoView {
+    use leptos::ev::Event;
+    use wasm_bindgen::JsCast;
+
+    let text = create_rw_signal(String::new());
+    let format = create_rw_signal(ImportFormat::Auto);
+    let sources = create_rw_signal(IMPORT_SOURCES.to_vec());
+    let sort = create_rw_signal((ImportSort::Free, false));
+    // Sources of the last check, the table's columns
+    let columns = create_rw_signal(Vec::<Sources>::new());
+    let import = create_action(
+        |(text, format, sources): &(String, ImportFormat, Vec<Sources>)| {
+            import_candidates(text.clone(), *format, sources.clone())
+        },
+    );
+
+    let checked = create_memo(move |_| {
+        sources.with(|sources| {
+            IMPORT_SOURCES
+                .into_iter()
+                .filter(|source| sources.contains(source))
+                .collect::<Vec<_>>()
+        })
+    });
+    let rows = move || match import.value().get() {
+        Some(Ok(mut rows)) => {
+            let (column, descending) = sort();
+            sort_rows(&mut rows, column, descending);
+            rows
+        }
+        _ => vec![],
+    };
+    let error = move || match import.value().get() {
+        Some(Err(error)) => Some(error.to_string()),
+        _ => None,
+    };
+
+    let set_format = move |ev: Event| {
+        let index = event_target_value(&ev).parse::<usize>().unwrap_or(0);
+        format.set(ImportFormat::ALL.get(index).copied().unwrap_or_default());
+    };
+    let toggle_source = move |source: Sources| {
+        sources.update(|sources| match sources.iter().position(|s| *s == source) {
+            Some(index) => {
+                sources.remove(index);
+            }
+            None => sources.push(source),
+        })
+    };
+    let load_file = move |ev: Event| {
+        let input = ev
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok());
+        let Some(file) = input
+            .and_then(|input| input.files())
+            .and_then(|files| files.get(0))
+        else {
+            return;
+        };
+        format.set(ImportFormat::from_file_name(&file.name()));
+        spawn_local(async move {
+            if let Ok(content) = wasm_bindgen_futures::JsFuture::from(file.text()).await {
+                text.set(content.as_string().unwrap_or_default());
+            }
+        });
+    };
+    let set_sort = move |column: ImportSort| {
+        sort.update(|(current, descending)| {
+            *descending = *current == column && !*descending;
+            *current = column;
+        })
+    };
+    let dispatch = move |_| {
+        columns.set(checked.get());
+        import.dispatch((text.get(), format.get(), checked.get()))
+    };
+
+    view! {
+        <div class="px-6 py-3 flex flex-col gap-2 text-sm">
+            <textarea
+                class="px-2 py-1 border font-mono h-40"
+                placeholder="Paste names, one per line, or CSV / JSON"
+                prop:value=text
+                on:input=move |ev| text.set(event_target_value(&ev))
+            ></textarea>
+            <div class="flex flex-wrap items-end gap-3">
+                <label class="flex flex-col">
+                    "File"
+                    <input type="file" accept=".txt,.csv,.tsv,.json" on:change=load_file/>
+                </label>
+                <label class="flex flex-col">
+                    "Format"
+                    <select class="px-1 py-1 border" on:change=set_format>
+                        {ImportFormat::ALL
+                            .iter()
+                            .enumerate()
+                            .map(|(index, option)| {
+                                let option = *option;
+                                view! {
+                                    <option
+                                        value=index.to_string()
+                                        selected=move || format() == option
+                                    >
+                                        {option.label()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                {IMPORT_SOURCES
+                    .into_iter()
+                    .map(|source| {
+                        view! {
+                            <label class="flex items-center gap-1">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || sources.with(|s| s.contains(&source))
+                                    on:change=move |_| toggle_source(source)
+                                />
+                                <span class=format!("w-4 h-4 {}", source.icon())></span>
+                                {source.label()}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
+                <button
+                    class="px-3 py-1 border bg-black text-white"
+                    disabled=move || import.pending().get()
+                    on:click=dispatch
+                >
+                    {move || if import.pending().get() { "Checking" } else { "Check" }}
+                </button>
+            </div>
+            {move || error().map(|error| view! { <p class="text-red-600">{error}</p> })}
+        </div>
+        <Show when=move || import.value().with(|value| matches!(value, Some(Ok(rows)) if !rows.is_empty()))>
+            <table class="w-full text-sm border-t">
+                <thead>
+                    <tr class="border-b text-left">
+                        <th class="px-6 py-2">
+                            <button on:click=move |_| set_sort(ImportSort::Name)>"Name"</button>
+                        </th>
+                        <th class="px-2 py-2">
+                            <button on:click=move |_| set_sort(ImportSort::Free)>"Free"</button>
+                        </th>
+                        {move || {
+                            columns()
+                                .into_iter()
+                                .map(|source| {
+                                    view! {
+                                        <th class="px-2 py-2">
+                                            <button
+                                                class="flex items-center gap-1"
+                                                on:click=move |_| set_sort(ImportSort::Source(source))
+                                            >
+                                                <span class=format!("w-4 h-4 {}", source.icon())></span>
+                                                {source.label()}
+                                            </button>
+                                        </th>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        rows()
+                            .into_iter()
+                            .map(|row| {
+                                let title = row.entry.title.clone();
+                                view! {
+                                    <tr class="border-b">
+                                        <td class="px-6 py-1 uppercase">
+                                            <button on:click=move |_| set_query_value(Some(title.clone()))>
+                                                {row.entry.title.clone()}
+                                            </button>
+                                        </td>
+                                        <td class="px-2 py-1">{row.availability.free()}</td>
+                                        {columns
+                                            .get_untracked()
+                                            .into_iter()
+                                            .map(|source| {
+                                                let available = row.availability.result(source);
+                                                view! {
+                                                    <td class="px-2 py-1">
+                                                        <StatusDot source available/>
+                                                    </td>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </tr>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </tbody>
+            </table>
+        </Show>
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn titles(text: &str, format: ImportFormat) -> Vec<(String, String)> {
+        parse_candidates(text, format)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.title, entry.link))
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(title, link)| (title.to_string(), link.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn quoted_fields() {
+        assert_eq!(
+            csv_fields("plain, \"with, comma\" ,\"say \"\"hi\"\"\"\ttabbed"),
+            ["plain", "with, comma", r#"say "hi""#, "tabbed"]
+        );
+    }
+
+    #[test]
+    fn csv_header_columns() {
+        let text = "rank,URL,Candidate\n1,https://a.example,Aurora\n2,,\"Borealis, Inc\"";
+        assert_eq!(
+            titles(text, ImportFormat::Auto),
+            pairs(&[("Aurora", "https://a.example"), ("Borealis, Inc", "")])
+        );
+        let text = "Title\tlink\nZephyr\thttps://z.example";
+        assert_eq!(
+            titles(text, ImportFormat::Csv),
+            pairs(&[("Zephyr", "https://z.example")])
+        );
+        assert_eq!(
+            titles("name\nnimbus", ImportFormat::Csv),
+            pairs(&[("nimbus", "")])
+        );
+    }
+
+    #[test]
+    fn csv_without_header() {
+        let text = "aurora,first\nborealis,second";
+        assert_eq!(
+            titles(text, ImportFormat::Auto),
+            pairs(&[("aurora", ""), ("borealis", "")])
+        );
+    }
+
+    #[test]
+    fn json_lists() {
+        assert_eq!(
+            titles(r#"["aurora", "borealis"]"#, ImportFormat::Auto),
+            pairs(&[("aurora", ""), ("borealis", "")])
+        );
+        let text = r#"[{"title": "aurora", "link": "https://a.example"}, {"name": "borealis", "url": "https://b.example"}, {"name": "cirrus"}]"#;
+        assert_eq!(
+            titles(text, ImportFormat::Json),
+            pairs(&[
+                ("aurora", "https://a.example"),
+                ("borealis", "https://b.example"),
+                ("cirrus", "")
+            ])
+        );
+        assert!(parse_candidates("[1, 2]", ImportFormat::Json).is_err());
+    }
+
+    #[test]
+    fn text_comments() {
+        let text = "# shortlist\naurora\n\n  # later\n  borealis  \n";
+        assert_eq!(
+            titles(text, ImportFormat::Auto),
+            pairs(&[("aurora", ""), ("borealis", "")])
+        );
+    }
+
+    #[test]
+    fn case_insensitive_dedup() {
+        assert_eq!(
+            titles("Aurora\naurora\nAURORA\nborealis", ImportFormat::Text),
+            pairs(&[("Aurora", ""), ("borealis", "")])
+        );
+        assert_eq!(
+            titles(r#"["Nimbus", "nimbus"]"#, ImportFormat::Json),
+            pairs(&[("Nimbus", "")])
+        );
+    }
+}
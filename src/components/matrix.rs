@@ -0,0 +1,176 @@
+use leptos::*;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Element, IntersectionObserver, IntersectionObserverEntry};
+
+use crate::components::{check_many, Availability, NameAvailability, Sources, StatusDot};
+
+/// Sources shown for every entry of the availability matrix
+pub const MATRIX_SOURCES: [Sources; 4] = [
+    Sources::PackageNpm,
+    Sources::OrgNpm,
+    Sources::Crates,
+    Sources::Github,
+];
+
+/// Titles sent in one batch check
+const MATRIX_BATCH: usize = 24;
+
+/// Lazily filled availability of every entry in a name grid, checked in batches as rows
+/// scroll into view
+#[derive(Clone, Copy)]
+pub struct AvailabilityMatrix {
+    results: RwSignal<HashMap<String, NameAvailability>>,
+    requested: StoredValue<HashSet<String>>,
+    queue: StoredValue<Vec<String>>,
+    scheduled: StoredValue<bool>,
+    /// Whether a batch is waiting on the server, only one is sent at a time
+    in_flight: StoredValue<bool>,
+    observer: StoredValue<Option<IntersectionObserver>>,
+}
+
+impl AvailabilityMatrix {
+    pub fn new() -> Self {
+        let matrix = AvailabilityMatrix {
+            results: create_rw_signal(HashMap::new()),
+            requested: store_value(HashSet::new()),
+            queue: store_value(vec![]),
+            scheduled: store_value(false),
+            in_flight: store_value(false),
+            observer: store_value(None),
+        };
+        on_cleanup(move || {
+            if let Some(observer) = matrix.observer.get_value() {
+                observer.disconnect();
+            }
+        });
+        matrix
+    }
+
+    /// Result of `title` on `source`, once its batch came back
+    pub fn result(&self, title: &str, source: Sources) -> Option<Availability> {
+        self.results
+            .with(|results| results.get(title).and_then(|result| result.result(source)))
+    }
+
+    /// Number of sources `title` is available on
+    pub fn free(&self, title: &str) -> usize {
+        self.results
+            .with(|results| results.get(title).map(|result| result.free()))
+            .unwrap_or(0)
+    }
+
+    /// Queues titles for checking whether or not their rows are visible
+    pub fn check(self, titles: impl IntoIterator<Item = String>) {
+        for title in titles {
+            self.enqueue(title);
+        }
+    }
+
+    /// Queues the row's `data-title` for checking when it becomes visible, unless it
+    /// already was
+    pub fn observe(self, element: &Element) {
+        if self.observer.with_value(Option::is_none) {
+            let callback = Closure::<dyn FnMut(js_sys::Array, IntersectionObserver)>::new(
+                move |entries: js_sys::Array, _: IntersectionObserver| {
+                    for entry in entries.iter() {
+                        let entry: IntersectionObserverEntry = entry.unchecked_into();
+                        if !entry.is_intersecting() {
+                            continue;
+                        }
+                        // Rows stay observed so a failed batch is retried on the next scroll
+                        if let Some(title) = entry.target().get_attribute("data-title") {
+                            self.enqueue(title);
+                        }
+                    }
+                },
+            );
+            let observer = IntersectionObserver::new(callback.as_ref().unchecked_ref()).ok();
+            // Lives as long as the observer, which is disconnected on cleanup
+            callback.forget();
+            self.observer.set_value(observer);
+        }
+        self.observer.with_value(|observer| {
+            if let Some(observer) = observer {
+                observer.observe(element);
+            }
+        });
+    }
+
+    fn enqueue(self, title: String) {
+        let queued = self
+            .requested
+            .try_update_value(|requested| requested.insert(title.clone()))
+            .unwrap_or(false);
+        if queued {
+            self.queue.update_value(|queue| queue.push(title));
+            self.schedule();
+        }
+    }
+
+    fn schedule(self) {
+        if !self.scheduled.get_value() {
+            self.scheduled.set_value(true);
+            // Rows scrolled in together end up in the same batch
+            set_timeout(move || self.flush(), Duration::from_millis(100));
+        }
+    }
+
+    fn flush(self) {
+        self.scheduled.set_value(false);
+        // The batch in flight schedules the next one when it comes back
+        if self.in_flight.get_value() {
+            return;
+        }
+        let titles: Vec<String> = self
+            .queue
+            .try_update_value(|queue| queue.drain(..queue.len().min(MATRIX_BATCH)).collect())
+            .unwrap_or_default();
+        if titles.is_empty() {
+            return;
+        }
+        self.in_flight.set_value(true);
+        spawn_local(async move {
+            match check_many(MATRIX_SOURCES.to_vec(), titles.clone()).await {
+                Ok(checked) => self.results.update(|results| {
+                    results.extend(
+                        checked
+                            .into_iter()
+                            .map(|result| (result.title.clone(), result)),
+                    )
+                }),
+                // Rows of a failed batch are queued again the next time they scroll into view
+                Err(_) => self.requested.update_value(|requested| {
+                    for title in &titles {
+                        requested.remove(title);
+                    }
+                }),
+            }
+            self.in_flight.set_value(false);
+            // Nothing is left to schedule once the grid was unmounted
+            if self.queue.try_with_value(|queue| !queue.is_empty()) == Some(true) {
+                self.schedule();
+            }
+        });
+    }
+}
+
+impl Default for AvailabilityMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Renders the status dots of one entry in the availability matrix
+#[component]
+pub fn MatrixDots(matrix: AvailabilityMatrix, title: String) -> impl IntoView {
+    MATRIX_SOURCES
+        .into_iter()
+        .map(|source| {
+            let title = title.clone();
+            let available = Signal::derive(move || matrix.result(&title, source));
+            view! { <StatusDot source available/> }
+        })
+        .collect_view()
+}
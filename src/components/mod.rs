@@ -1,7 +1,21 @@
 mod available;
 mod badge;
+mod corpus_picker;
+mod details;
 mod error_template;
+mod filters;
+mod generate;
+mod github;
+mod history;
+mod import;
+mod matrix;
 mod names;
 mod query_input;
+mod report;
+mod suggestions;
 
-pub use self::{available::*, badge::*, error_template::*, names::*, query_input::*};
+pub use self::{
+    available::*, badge::*, corpus_picker::*, details::*, error_template::*, filters::*,
+    generate::*, github::*, history::*, import::*, matrix::*, names::*, query_input::*, report::*,
+    suggestions::*,
+};
@@ -1,156 +1,196 @@
 use leptos::*;
-use scraper::Html;
-use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 
-use crate::state::{AppCache, CacheData};
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct Flag {
-    title: String,
-    link: String,
-    src: String,
+use crate::components::{AvailabilityMatrix, MatrixDots, NameFilterBar, NameFilters};
+pub use crate::corpus::{Entry, Flag};
+use crate::search::fuzzy_match;
+use crate::signals::debounce_signal;
+
+/// Entries of a corpus, fetched and parsed on the server, the default corpus for an empty id
+#[server(CorpusNames, "/api")]
+pub async fn corpus_names(id: String) -> Result<Vec<Entry>, ServerFnError> {
+    use crate::corpus::{find_corpus, load_entries};
+
+    let corpus = find_corpus(&id)
+        .ok_or_else(|| ServerFnError::ServerError(format!("Unknown corpus {}", id)))?;
+    let client = use_context::<reqwest::Client>()
+        .ok_or_else(|| ServerFnError::ServerError("Missing client context".to_string()))?;
+    Ok(load_entries(&client, corpus).await?.as_ref().clone())
 }
 
-pub fn scrape_flag(flag: scraper::ElementRef<'_>) -> Option<Flag> {
-    let title = flag
-        .select(&scraper::Selector::parse("a").unwrap())
-        .next()
-        .and_then(|a| a.value().attr("title"))?;
-    let src = flag
-        .select(&scraper::Selector::parse("img").unwrap())
-        .next()
-        .and_then(|a| a.value().attr("src"))?;
-    let link = flag
-        .select(&scraper::Selector::parse("a").unwrap())
-        .next()
-        .and_then(|a| a.value().attr("href"))?;
-
-    Some(Flag {
-        title: title.to_string().clone(),
-        src: src.to_string().clone(),
-        link: link.to_string().clone(),
-    })
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct Entry {
-    link: String,
-    title: String,
-    flags: Vec<Flag>,
-}
-
-pub fn scrape_entry(item: scraper::ElementRef<'_>) -> Option<Entry> {
-    let title: String = item
-        .select(&scraper::Selector::parse("a").unwrap())
-        .next()
-        .and_then(|a| Some(a.text().collect::<String>().trim().to_owned()))?;
-    let link = item
-        .select(&scraper::Selector::parse("a").unwrap())
-        .next()
-        .and_then(|a| a.value().attr("href"))
-        .and_then(|f| Some(format!("{}{}", "https://en.wikipedia.org", f)))?;
-
-    let mut flags = vec![];
-
-    for flag in item.select(&scraper::Selector::parse(".flagicon").unwrap()) {
-        match scrape_flag(flag) {
-            Some(flag) => flags.push(flag),
-            _ => {}
+#[component]
+pub fn Names(
+    #[prop(into)] corpus: Signal<String>,
+    /// Narrows the grid to fuzzy matches, best first
+    #[prop(into)]
+    query: Signal<String>,
+    set_query_value: SignalSetter<Option<String>>,
+) -> impl IntoView {
+    // Loaded during SSR and serialized into the stream, so hydration doesn't refetch
+    let names = create_resource(corpus, corpus_names);
+    let value = move || match names.get() {
+        Some(Ok(v)) => v,
+        _ => vec![],
+    };
+    let error = move || match names.get() {
+        Some(Err(error)) => Some(error.to_string()),
+        _ => None,
+    };
+    let (show_matrix, set_show_matrix) = create_signal(false);
+    let matrix = AvailabilityMatrix::new();
+    let filters = NameFilters::from_query();
+
+    let flags = Signal::derive(move || {
+        let mut flags: Vec<String> = value()
+            .into_iter()
+            .flat_map(|entry| entry.flags.into_iter().map(|flag| flag.title))
+            .collect();
+        flags.sort();
+        flags.dedup();
+        flags
+    });
+    // Filtering on availability can't wait for rows to scroll into view
+    create_effect(move |_| {
+        if filters.needs_availability() {
+            matrix.check(value().into_iter().map(|entry| entry.title));
         }
-    }
-
-    let mut unique_set: HashSet<_> = HashSet::new();
-
-    // Filter the vector for unique elements based on the 'field' property
-    let flags: Vec<_> = flags
-        .into_iter()
-        .filter(move |item| unique_set.insert(item.src.clone()))
-        .collect();
+    });
+    let search = debounce_signal(std::time::Duration::from_millis(200), query);
+    let filtered = move || {
+        let entries = filters.apply(value(), matrix);
+        let query = search();
+        if query.trim().is_empty() {
+            return entries;
+        }
+        let mut ranked: Vec<_> = entries
+            .into_iter()
+            .filter_map(|entry| Some((fuzzy_match(&query, &entry.title)?.score, entry)))
+            .collect();
+        ranked.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        ranked.into_iter().map(|(_, entry)| entry).collect()
+    };
+    let highlight = Signal::derive(search);
 
-    if flags.len() == 0 {
-        None
-    } else {
-        Some(Entry { title, link, flags })
+    view! {
+        <Suspense fallback=|| "Loading">
+            <NameFilterBar filters flags/>
+            <label class="flex items-center gap-1 px-6 pb-2 text-sm">
+                <input
+                    type="checkbox"
+                    prop:checked=show_matrix
+                    on:change=move |_| set_show_matrix.update(|show| *show = !*show)
+                />
+                "Availability"
+            </label>
+            {move || {
+                error().map(|error| view! { <p class="px-6 py-2 text-sm text-red-600">{error}</p> })
+            }}
+            <Show
+                when=move || show_matrix() || filters.needs_availability()
+                fallback=move || view! { <NameGrid entries=filtered set_query_value highlight/> }
+            >
+                <NameGrid entries=filtered set_query_value matrix highlight/>
+            </Show>
+        </Suspense>
     }
 }
 
-pub fn scrape_entries(text: &String) -> Option<Vec<Entry>> {
-    let document = Html::parse_document(&text);
-    let select_items = scraper::Selector::parse(".mw-parser-output ul > li").unwrap();
-    let items = document.select(&select_items);
-    let mut entries = vec![];
-    for item in items {
-        match scrape_entry(item) {
-            Some(entry) => entries.push(entry),
-            _ => {}
+/// Renders a title with runs of fuzzy matched characters marked
+fn highlighted(title: &str, query: &str) -> View {
+    let positions = fuzzy_match(query, title)
+        .map(|found| found.positions)
+        .unwrap_or_default();
+    let mut runs: Vec<(String, bool)> = vec![];
+    for (index, c) in title.chars().enumerate() {
+        let matched = positions.contains(&index);
+        match runs.last_mut() {
+            Some((run, run_matched)) if *run_matched == matched => run.push(c),
+            _ => runs.push((c.to_string(), matched)),
         }
     }
-    return Some(entries);
-}
-
-// #[server(WW2Names, "/api")]
-pub async fn ww2_names() -> Result<Vec<Entry>, ServerFnError> {
-    let url = "https://en.wikipedia.org/wiki/List_of_World_War_II_military_operations";
-    let cache = use_context::<AppCache>().expect("Missing context provider");
-    if let Some(CacheData::Html(str)) = cache.get_value(url.to_string().clone()) {
-        return Ok(scrape_entries(&str).unwrap_or(vec![]));
-    }
-    let result = reqwest::get(url).await?;
-    let text = result.text().await?;
-    cache.set_value(
-        url.to_string().clone(),
-        CacheData::Html(text.to_string().clone()),
-    );
-    Ok(scrape_entries(&text).unwrap_or(vec![]))
+    runs.into_iter()
+        .map(|(run, matched)| match matched {
+            true => view! { <mark class="bg-yellow-200">{run}</mark> }.into_view(),
+            false => run.into_view(),
+        })
+        .collect_view()
 }
 
+/// Renders entries as a grid of names which fill the query when clicked
 #[component]
-pub fn Names(set_query_value: SignalSetter<Option<String>>) -> impl IntoView {
-    let value = create_resource(|| (), |_| ww2_names());
-    let value = move || match value.get() {
-        Some(Ok(v)) => v,
-        _ => vec![],
-    };
-
+pub fn NameGrid<F: Fn() -> Vec<Entry> + 'static>(
+    entries: F,
+    set_query_value: SignalSetter<Option<String>>,
+    /// Shows per source status dots, checked as rows scroll into view
+    #[prop(optional)]
+    matrix: Option<AvailabilityMatrix>,
+    /// Marks the characters of each title matched by this query
+    #[prop(optional, into)]
+    highlight: Option<Signal<String>>,
+) -> impl IntoView {
     view! {
-        <Suspense fallback=|| "Loading">
-            <div class="w-full grid grid-cols-4 border-t">
-                <For
-                    each=move || value()
-                    key=move |x| x.link.clone()
-                    children=move |x| {
-                        let title = x.title.clone();
-                        view! {
-                            <div class="flex items-start gap-1 border-b px-6 py-2 uppercase bg-white">
-                                <button
-                                    target="__blank"
-                                    class="leading-none"
-                                    on:click=move |_| set_query_value(Some(title.clone()))
-                                >
-                                    {x.title.clone()}
-                                </button>
-                                <For
-                                    each=move || x.flags.clone().into_iter()
-                                    key=move |x| x.link.clone()
-                                    children=move |x| {
-                                        view! {
-                                            <div
-                                                class="aspect-[2/1] w-[1.5rem] mt-[2px] bg-cover flex-shrink-0"
-                                                style:background-image=format!("url('{}')", x.src)
-                                            >// <img class="w-6" src=x.src />
-                                            </div>
-                                        }
-                                            .into_view()
+        <div class="w-full grid grid-cols-4 border-t">
+            <For
+                each=entries
+                key=move |x| (x.link.clone(), x.title.clone())
+                children=move |x| {
+                    let title = x.title.clone();
+                    let row = create_node_ref::<html::Div>();
+                    if let Some(matrix) = matrix {
+                        row.on_load(move |row| matrix.observe(&row));
+                    }
+                    view! {
+                        <div
+                            class="flex items-start gap-1 border-b px-6 py-2 uppercase bg-white"
+                            node_ref=row
+                            data-title=x.title.clone()
+                        >
+                            <button
+                                target="__blank"
+                                class="leading-none"
+                                on:click=move |_| set_query_value(Some(title.clone()))
+                            >
+                                {
+                                    let title = x.title.clone();
+                                    move || match highlight {
+                                        Some(query) => highlighted(&title, &query()),
+                                        None => title.clone().into_view(),
                                     }
-                                />
+                                }
+                            </button>
+                            {x
+                                .image
+                                .clone()
+                                .map(|src| {
+                                    view! { <img class="h-4 w-auto flex-shrink-0" src=src alt=""/> }
+                                })}
+                            <For
+                                each=move || x.flags.clone().into_iter()
+                                key=move |x| x.link.clone()
+                                children=move |x| {
+                                    view! {
+                                        <div
+                                            class="aspect-[2/1] w-[1.5rem] mt-[2px] bg-cover flex-shrink-0"
+                                            style:background-image=format!("url('{}')", x.src)
+                                        >// <img class="w-6" src=x.src />
+                                        </div>
+                                    }
+                                        .into_view()
+                                }
+                            />
+                            {matrix
+                                .map(|matrix| {
+                                    view! {
+                                        <span class="ml-auto flex gap-1">
+                                            <MatrixDots matrix title=x.title.clone()/>
+                                        </span>
+                                    }
+                                })}
 
-                            </div>
-                        }
+                        </div>
                     }
-                />
+                }
+            />
 
-            </div>
-        </Suspense>
+        </div>
     }
 }
@@ -1,11 +1,16 @@
-use crate::components::{Available, Sources};
+use crate::components::{
+    name_path, parse_owners, Available, GithubAvailable, Sources, Suggestions,
+};
 use leptos::*;
+use leptos_router::A;
 
 /// Renders search input field
 #[component]
 pub fn QueryInput(
     query_value: Memo<Option<String>>,
     set_query_value: SignalSetter<Option<String>>,
+    owners_value: Memo<Option<String>>,
+    set_owners_value: SignalSetter<Option<String>>,
 ) -> impl IntoView {
     use leptos::ev::Event;
     use leptos::html::Input;
@@ -13,6 +18,9 @@ pub fn QueryInput(
     let input_ref = create_node_ref::<Input>();
     let handle_input = move |ev: Event| set_query_value(Some(event_target_value(&ev)));
     let query = create_memo(move |_| query_value().unwrap_or("".to_string()));
+    let handle_owners = move |ev: Event| set_owners_value(Some(event_target_value(&ev)));
+    let owners_text = create_memo(move |_| owners_value().unwrap_or("".to_string()));
+    let owners = create_memo(move |_| parse_owners(&owners_text()));
 
     create_effect(move |_| {
         let node = input_ref.get().expect("Input should be loaded");
@@ -34,13 +42,31 @@ pub fn QueryInput(
                 value=query
                 on:input=handle_input
             />
-            <div class="flex gap-3 my-1">
+            <div class="flex flex-wrap items-start gap-3 my-1">
                 <Show when=move || { query().len() > 0 }>
                     <Available source=Sources::PackageNpm query=query/>
                     <Available source=Sources::OrgNpm query=query/>
-                    <Available source=Sources::Github query=query/>
+                    <Available source=Sources::Crates query=query/>
+                    <GithubAvailable query=query owners=owners/>
+                    <A class="text-sm underline self-center" href=move || name_path(&query())>
+                        "Full report"
+                    </A>
                 </Show>
             </div>
+            <Show when=move || { !query().is_empty() }>
+                <Suggestions query set_query_value/>
+            </Show>
+            <label for="owners" class="text-sm pl-3 pb-1">
+                "Check repository names under owners"
+            </label>
+            <input
+                type="text"
+                id="owners"
+                class="px-3 py-1 border w-full text-sm"
+                placeholder="Comma separated owners, e.g. rust-lang, tokio-rs"
+                value=owners_text
+                on:input=handle_owners
+            />
         </div>
     }
 }
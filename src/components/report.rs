@@ -0,0 +1,304 @@
+use leptos::*;
+use leptos_router::A;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{CheckStatus, SourceResult};
+use crate::badge::source_label;
+use crate::components::{NameAvailability, NameDetails, SourceIcon, Sources, StatusDot, Timeline};
+use crate::history::{display_time, NameHistory};
+
+/// Everything known about a name on one source
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct SourceReport {
+    pub source: Sources,
+    pub result: SourceResult,
+    /// Naming rules of the registry the name breaks
+    pub problems: Vec<String>,
+    /// Registry page of the name
+    pub link: String,
+    /// Whoever holds the name, when it's taken
+    pub details: Option<NameDetails>,
+}
+
+/// A full check of one name, shown on its report page
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct NameReport {
+    pub name: String,
+    /// Unix time the report was made
+    pub checked_at: u64,
+    pub sources: Vec<SourceReport>,
+    /// Existing names that block this one or are variants of it taken everywhere
+    pub similar: Vec<String>,
+    /// Variants free on at least one source, most free first
+    pub variants: Vec<NameAvailability>,
+    /// Earlier results, this report's included
+    pub history: NameHistory,
+}
+
+impl NameReport {
+    /// Number of sources where the name is free
+    pub fn free(&self) -> usize {
+        self.sources
+            .iter()
+            .filter(|source| source.result.status == CheckStatus::Available)
+            .count()
+    }
+}
+
+/// Checks `name` on every source it applies to, with holder details and variants
+#[server(GetNameReport, "/api")]
+pub async fn name_report(name: String) -> Result<NameReport, ServerFnError> {
+    use crate::api::{check_names, parse_name};
+    use crate::components::{
+        check_many, taken_details, variants, VariantRules, SUGGESTION_SOURCES,
+    };
+    use crate::history::History;
+    use crate::state::{AppCache, CheckLimit};
+    use crate::store::unix_now;
+    use futures::future::join_all;
+    use nameit_core::check::{name_problems, registry_url};
+
+    let name = parse_name(&name).map_err(|error| ServerFnError::ServerError(error.message))?;
+    let cache = use_context::<AppCache>().expect("Missing context provider");
+    let client = use_context::<reqwest::Client>().expect("Missing context provider");
+
+    // Repositories only make sense for `owner/name`
+    let sources = Sources::ALL
+        .into_iter()
+        .filter(|source| (*source == Sources::GithubRepo) == name.contains('/'))
+        .collect::<Vec<_>>();
+    let history = use_context::<History>().expect("Missing context provider");
+    let limit = use_context::<CheckLimit>().expect("Missing context provider");
+    let mut results = check_names(&client, &cache, &limit.0, vec![name.clone()], &sources).await;
+    history.record_names(&results);
+    let results = results.remove(0).results;
+
+    let details = join_all(sources.iter().zip(&results).map(|(source, result)| {
+        let (source, name) = (*source, name.clone());
+        let taken = result.status == CheckStatus::Taken;
+        async move {
+            match taken {
+                true => taken_details(source, name).await.ok().flatten(),
+                false => None,
+            }
+        }
+    }))
+    .await;
+    let mut similar = results
+        .iter()
+        .filter_map(|result| result.similar_to.clone())
+        .collect::<Vec<_>>();
+    let reports = sources
+        .into_iter()
+        .zip(results)
+        .zip(details)
+        .map(|((source, result), details)| SourceReport {
+            problems: name_problems(source, &name),
+            link: details.as_ref().map_or_else(
+                || registry_url(source, &name),
+                |details| details.link.clone(),
+            ),
+            source,
+            result,
+            details,
+        })
+        .collect();
+
+    let mut variants = check_many(
+        SUGGESTION_SOURCES.to_vec(),
+        variants(&name, &VariantRules::default()),
+    )
+    .await?;
+    for variant in variants.iter().filter(|variant| variant.taken_everywhere()) {
+        if !similar.contains(&variant.title) {
+            similar.push(variant.title.clone());
+        }
+    }
+    variants.retain(|variant| variant.free() > 0);
+    variants.sort_by(|a, b| {
+        b.free()
+            .cmp(&a.free())
+            .then(a.title.len().cmp(&b.title.len()))
+    });
+
+    Ok(NameReport {
+        history: history.get(&name),
+        name,
+        checked_at: unix_now(),
+        sources: reports,
+        similar,
+        variants,
+    })
+}
+
+/// Report page of a name, the name escaped as a single path segment
+pub fn name_path(name: &str) -> String {
+    format!("/name/{}", utf8_percent_encode(name, NON_ALPHANUMERIC))
+}
+
+fn status_text(result: &SourceResult) -> String {
+    match (result.status, &result.similar_to) {
+        (CheckStatus::Available, _) => "Available".to_string(),
+        (CheckStatus::Taken, _) => "Taken".to_string(),
+        (CheckStatus::Similar, Some(similar)) => format!("Blocked by similarity to {}", similar),
+        (CheckStatus::Similar, None) => "Blocked by similarity".to_string(),
+        (CheckStatus::Error, _) => "Couldn't be checked".to_string(),
+    }
+}
+
+fn status_class(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Available => "text-green-800",
+        CheckStatus::Taken => "text-red-800",
+        CheckStatus::Similar => "text-amber-600",
+        CheckStatus::Error => "text-black/50",
+    }
+}
+
+/// Renders one row of the report table
+#[component]
+fn SourceRow(report: SourceReport) -> impl IntoView {
+    let details = report.details.map(|details| {
+        view! {
+            <dl class="grid grid-cols-[auto_1fr] gap-x-2 text-xs">
+                {details
+                    .rows()
+                    .into_iter()
+                    .map(|(label, value)| {
+                        view! {
+                            <dt class="text-black/50">{label}</dt>
+                            <dd class="break-words">{value}</dd>
+                        }
+                    })
+                    .collect_view()}
+            </dl>
+        }
+    });
+
+    view! {
+        <tr class="border-t align-top">
+            <td class="py-2 pr-4">
+                <span class="flex items-center gap-1">
+                    <span class=format!("w-6 h-6 {}", report.source.icon())></span>
+                    {source_label(report.source)}
+                </span>
+            </td>
+            <td class="py-2 pr-4">
+                <p class=status_class(report.result.status)>{status_text(&report.result)}</p>
+                {report.result.error.map(|error| view! { <p class="text-xs text-black/50">{error}</p> })}
+                <ul class="text-xs text-amber-700">
+                    {report.problems.into_iter().map(|problem| view! { <li>{problem}</li> }).collect_view()}
+                </ul>
+            </td>
+            <td class="py-2 pr-4">{details}</td>
+            <td class="py-2">
+                <a class="underline" href=report.link target="_blank">
+                    "Registry page"
+                </a>
+            </td>
+        </tr>
+    }
+}
+
+/// Renders the full report on a name
+#[component]
+pub fn Report(#[prop(into)] name: Signal<String>) -> impl IntoView {
+    let report = create_resource(name, name_report);
+
+    let content = move || match report.get() {
+        Some(Ok(report)) => {
+            let summary = format!(
+                "Free on {} of {} sources, checked {}",
+                report.free(),
+                report.sources.len(),
+                display_time(report.checked_at),
+            );
+            let similar = (!report.similar.is_empty()).then(|| {
+                view! {
+                    <h2 class="text-lg font-semibold">"Similar taken names"</h2>
+                    <ul class="flex flex-wrap gap-3 text-sm">
+                        {report
+                            .similar
+                            .into_iter()
+                            .map(|similar| {
+                                view! {
+                                    <li>
+                                        <A class="underline" href=name_path(&similar)>
+                                            {similar}
+                                        </A>
+                                    </li>
+                                }
+                            })
+                            .collect_view()}
+                    </ul>
+                }
+            });
+            let variants = match report.variants.is_empty() {
+                true => view! { <p class="text-sm text-black/50">"No free variants found"</p> }
+                    .into_view(),
+                false => view! {
+                    <ul class="flex flex-col gap-1 text-sm">
+                        {report
+                            .variants
+                            .into_iter()
+                            .map(|variant| {
+                                view! {
+                                    <li class="flex items-center gap-2">
+                                        <A class="underline" href=name_path(&variant.title)>
+                                            {variant.title}
+                                        </A>
+                                        {variant
+                                            .results
+                                            .into_iter()
+                                            .map(|(source, result)| {
+                                                view! { <StatusDot source available=Some(result)/> }
+                                            })
+                                            .collect_view()}
+                                    </li>
+                                }
+                            })
+                            .collect_view()}
+                    </ul>
+                }
+                .into_view(),
+            };
+            view! {
+                <p class="text-sm text-black/50">
+                    {summary}
+                </p>
+                <table class="text-sm text-left">
+                    <thead>
+                        <tr class="text-black/50">
+                            <th class="pr-4 font-normal">"Source"</th>
+                            <th class="pr-4 font-normal">"Result"</th>
+                            <th class="pr-4 font-normal">"Holder"</th>
+                            <th class="font-normal"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {report
+                            .sources
+                            .into_iter()
+                            .map(|report| view! { <SourceRow report/> })
+                            .collect_view()}
+                    </tbody>
+                </table>
+                {similar}
+                <h2 class="text-lg font-semibold">"Suggested variants"</h2>
+                {variants}
+                <h2 class="text-lg font-semibold">"History"</h2>
+                <Timeline history=report.history/>
+            }
+            .into_view()
+        }
+        Some(Err(e)) => view! { <p class="text-red-800">{e.to_string()}</p> }.into_view(),
+        None => ().into_view(),
+    };
+
+    view! {
+        <div class="flex flex-col gap-3">
+            <Suspense fallback=|| view! { <p class="text-black/50">"Checking"</p> }>{content}</Suspense>
+        </div>
+    }
+}
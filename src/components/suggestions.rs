@@ -0,0 +1,261 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{NameAvailability, Sources, StatusDot};
+use crate::signals::debounce_signal;
+
+/// Sources suggested variants are checked against
+pub const SUGGESTION_SOURCES: [Sources; 4] = [
+    Sources::PackageNpm,
+    Sources::OrgNpm,
+    Sources::Crates,
+    Sources::Github,
+];
+
+/// Rules used to derive variants of a taken name
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct VariantRules {
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+    #[serde(default)]
+    pub suffixes: Vec<String>,
+    pub drop_vowels: bool,
+    pub pluralize: bool,
+    pub hyphenate: bool,
+}
+
+impl Default for VariantRules {
+    fn default() -> Self {
+        VariantRules {
+            prefixes: vec!["get".to_string()],
+            suffixes: ["-js", "-rs", "hq", "app"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            drop_vowels: true,
+            pluralize: true,
+            hyphenate: true,
+        }
+    }
+}
+
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    for c in name.trim().chars() {
+        if c.is_whitespace() || c == '_' || c == '-' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && word.chars().last().is_some_and(|l| l.is_lowercase()) {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words.into_iter().map(|w| w.to_lowercase()).collect()
+}
+
+fn pluralize(name: &str) -> String {
+    let consonant_y = name.ends_with('y')
+        && !name
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|c| "aeiou".contains(c));
+    if consonant_y {
+        format!("{}ies", &name[..name.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|end| name.ends_with(end))
+    {
+        format!("{}es", name)
+    } else {
+        format!("{}s", name)
+    }
+}
+
+fn drop_vowels(name: &str) -> String {
+    name.chars()
+        .enumerate()
+        .filter(|(i, c)| *i == 0 || !"aeiou".contains(*c))
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Generates unique variants of `name` according to `rules`, excluding the name itself
+pub fn variants(name: &str, rules: &VariantRules) -> Vec<String> {
+    let words = split_words(name);
+    if words.is_empty() {
+        return vec![];
+    }
+    let joined = words.concat();
+    let hyphenated = words.join("-");
+
+    let mut variants = vec![];
+    if rules.hyphenate {
+        variants.push(hyphenated.clone());
+        variants.push(joined.clone());
+    }
+    for prefix in &rules.prefixes {
+        variants.push(format!("{}{}", prefix.trim().to_lowercase(), joined));
+    }
+    for suffix in &rules.suffixes {
+        variants.push(format!("{}{}", joined, suffix.trim().to_lowercase()));
+    }
+    if rules.drop_vowels {
+        variants.push(drop_vowels(&joined));
+    }
+    if rules.pluralize {
+        variants.push(pluralize(&joined));
+    }
+
+    let original = name.trim().to_lowercase();
+    let mut seen = std::collections::HashSet::new();
+    variants
+        .into_iter()
+        .filter(|variant| !variant.is_empty() && *variant != original)
+        .filter(|variant| seen.insert(variant.clone()))
+        .collect()
+}
+
+/// Ranks variants of `title` by free sources, `None` unless `title` is taken on every source
+#[server(SuggestVariants, "/api")]
+pub async fn suggest_variants(
+    title: String,
+    rules: VariantRules,
+) -> Result<Option<Vec<NameAvailability>>, ServerFnError> {
+    use crate::components::check_many;
+
+    let sources = SUGGESTION_SOURCES.to_vec();
+    let checked = check_many(sources.clone(), vec![title.clone()]).await?;
+    if !checked.iter().all(NameAvailability::taken_everywhere) {
+        return Ok(None);
+    }
+
+    let mut ranked = check_many(sources, variants(&title, &rules)).await?;
+    ranked.sort_by(|a, b| {
+        b.free()
+            .cmp(&a.free())
+            .then(a.title.len().cmp(&b.title.len()))
+    });
+    Ok(Some(ranked))
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Renders ranked variants of a query that is taken on every source
+#[component]
+pub fn Suggestions(
+    query: Memo<String>,
+    set_query_value: SignalSetter<Option<String>>,
+) -> impl IntoView {
+    use leptos::ev::Event;
+
+    let debounced = debounce_signal(std::time::Duration::from_millis(300), query);
+    let rules = create_rw_signal(VariantRules::default());
+
+    let suggestions = create_resource(
+        move || (debounced(), rules()),
+        |(query, rules)| suggest_variants(query, rules),
+    );
+    let suggestions = move || match suggestions.get() {
+        Some(Ok(Some(suggestions))) => Some(suggestions),
+        _ => None,
+    };
+
+    let set_prefixes =
+        move |ev: Event| rules.update(|r| r.prefixes = parse_list(&event_target_value(&ev)));
+    let set_suffixes =
+        move |ev: Event| rules.update(|r| r.suffixes = parse_list(&event_target_value(&ev)));
+
+    view! {
+        <Transition fallback=|| ()>
+            <Show when=move || suggestions().is_some()>
+                <div class="border px-3 py-2 my-2 text-sm">
+                    <p class="pb-1">"Taken everywhere, try one of these"</p>
+                    <div class="flex flex-wrap gap-3 pb-2 text-xs">
+                        <label class="flex items-center gap-1">
+                            "Prefixes"
+                            <input
+                                type="text"
+                                class="px-1 border"
+                                value=move || rules.with(|r| r.prefixes.join(", "))
+                                on:change=set_prefixes
+                            />
+                        </label>
+                        <label class="flex items-center gap-1">
+                            "Suffixes"
+                            <input
+                                type="text"
+                                class="px-1 border"
+                                value=move || rules.with(|r| r.suffixes.join(", "))
+                                on:change=set_suffixes
+                            />
+                        </label>
+                        <label class="flex items-center gap-1">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || rules.with(|r| r.drop_vowels)
+                                on:change=move |_| rules.update(|r| r.drop_vowels = !r.drop_vowels)
+                            />
+                            "Drop vowels"
+                        </label>
+                        <label class="flex items-center gap-1">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || rules.with(|r| r.pluralize)
+                                on:change=move |_| rules.update(|r| r.pluralize = !r.pluralize)
+                            />
+                            "Pluralize"
+                        </label>
+                        <label class="flex items-center gap-1">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || rules.with(|r| r.hyphenate)
+                                on:change=move |_| rules.update(|r| r.hyphenate = !r.hyphenate)
+                            />
+                            "Hyphenate"
+                        </label>
+                    </div>
+                    <ul class="flex flex-col gap-1">
+                        <For
+                            each=move || suggestions().unwrap_or_default()
+                            key=|suggestion| suggestion.title.clone()
+                            children=move |suggestion| {
+                                let title = suggestion.title.clone();
+                                view! {
+                                    <li class="flex items-center gap-2">
+                                        <button
+                                            class="underline"
+                                            on:click=move |_| set_query_value(Some(title.clone()))
+                                        >
+                                            {suggestion.title.clone()}
+                                        </button>
+                                        {suggestion
+                                            .results
+                                            .into_iter()
+                                            .map(|(source, result)| {
+                                                view! { <StatusDot source available=Some(result)/> }
+                                            })
+                                            .collect_view()}
+                                    </li>
+                                }
+                            }
+                        />
+                    </ul>
+                </div>
+            </Show>
+        </Transition>
+    }
+}
@@ -0,0 +1,274 @@
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+pub use nameit_core::corpus::*;
+
+/// Corpora the server has definitions for, so the client never needs its own copy
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CorpusList {
+    /// Corpus shown when none is picked
+    pub default: String,
+    pub corpora: Vec<CorpusInfo>,
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use leptos::ServerFnError;
+        use std::path::Path;
+        use std::sync::OnceLock;
+
+        /// Definitions compiled into the binary, used unless the server loads its own
+        const BUNDLED_DEFINITIONS: &str = include_str!("../../corpora.toml");
+
+        static CORPORA: OnceLock<Vec<Box<dyn NameCorpus>>> = OnceLock::new();
+
+        fn boxed(corpora: Vec<SelectorCorpus>) -> Vec<Box<dyn NameCorpus>> {
+            corpora
+                .into_iter()
+                .map(|corpus| Box::new(corpus) as Box<dyn NameCorpus>)
+                .collect()
+        }
+
+        /// Every corpus available, in definition order
+        pub fn corpora() -> &'static [Box<dyn NameCorpus>] {
+            CORPORA.get_or_init(|| {
+                let definitions = parse_definitions(BUNDLED_DEFINITIONS, false)
+                    .expect("bundled corpus definitions are invalid");
+                boxed(definitions)
+            })
+        }
+
+        /// A corpus by id, the default one when the id is empty
+        pub fn find_corpus(id: &str) -> Option<&'static dyn NameCorpus> {
+            let id = match id {
+                "" => default_corpus(),
+                id => id,
+            };
+            corpora()
+                .iter()
+                .find(|corpus| corpus.id() == id)
+                .map(|corpus| corpus.as_ref())
+        }
+
+        /// The first defined corpus, shown when no corpus is picked
+        pub fn default_corpus() -> &'static str {
+            corpora()
+                .first()
+                .map(|corpus| corpus.id())
+                .unwrap_or_default()
+        }
+
+        /// Replaces the bundled corpora with the definitions in a `.toml` or `.json` file,
+        /// has to run before the first corpus lookup
+        pub fn load_corpora(path: &Path) -> Result<usize, CorpusError> {
+            let text = std::fs::read_to_string(path)?;
+            let json = path.extension().is_some_and(|extension| extension == "json");
+            let definitions = parse_definitions(&text, json)?;
+            let count = definitions.len();
+            CORPORA
+                .set(boxed(definitions))
+                .map_err(|_| CorpusError::AlreadyLoaded)?;
+            Ok(count)
+        }
+
+        use include_dir::{include_dir, Dir};
+        use std::collections::BTreeMap;
+        use std::sync::{Arc, Mutex, PoisonError};
+        use std::time::{Duration, Instant};
+
+        /// Entries captured with `cargo run --features ssr -- snapshot`, compiled in as an offline
+        /// fallback
+        static SNAPSHOTS: Dir = include_dir!("$CARGO_MANIFEST_DIR/corpora/snapshots");
+
+        /// Serves only snapshots when set, never fetching corpus pages
+        pub const OFFLINE_VAR: &str = "NAMEIT_OFFLINE";
+
+        pub fn offline() -> bool {
+            std::env::var(OFFLINE_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+        }
+
+        /// Bundled entries of a corpus, if it has a snapshot
+        pub fn snapshot(corpus: &dyn NameCorpus) -> Option<Vec<Entry>> {
+            let file = SNAPSHOTS.get_file(format!("{}.json", corpus.id()))?;
+            serde_json::from_slice(file.contents()).ok()
+        }
+
+        /// Fails when a corpus has no readable snapshot, which an offline server can't serve
+        pub fn check_snapshots() -> Result<(), ServerFnError> {
+            let missing = corpora()
+                .iter()
+                .filter(|corpus| snapshot(corpus.as_ref()).is_none())
+                .map(|corpus| corpus.id())
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                return Ok(());
+            }
+            Err(ServerFnError::ServerError(format!(
+                "no snapshot of corpus {}, run `cargo run --features ssr -- snapshot` with network access",
+                missing.join(", ")
+            )))
+        }
+
+        /// Fetches every corpus and writes its entries to `<dir>/<id>.json`, failing after the
+        /// others are written when any corpus couldn't be fetched or had no entries
+        pub async fn write_snapshots(client: &reqwest::Client, dir: &Path) -> Result<usize, ServerFnError> {
+            std::fs::create_dir_all(dir)?;
+            let mut written = 0;
+            let mut failed = vec![];
+            for corpus in corpora() {
+                let document = match corpus.fetch(client).await {
+                    Ok(document) => document,
+                    Err(error) => {
+                        log::warn!("couldn't fetch corpus {}: {}", corpus.id(), error);
+                        failed.push(corpus.id());
+                        continue;
+                    }
+                };
+                let entries = corpus.parse(&document);
+                if entries.is_empty() {
+                    log::warn!("no entries found for corpus {}", corpus.id());
+                    failed.push(corpus.id());
+                    continue;
+                }
+                let json = serde_json::to_string_pretty(&entries)? + "\n";
+                std::fs::write(dir.join(format!("{}.json", corpus.id())), json)?;
+                log::info!("{} entries in corpus {}", entries.len(), corpus.id());
+                written += 1;
+            }
+            if !failed.is_empty() {
+                return Err(ServerFnError::ServerError(format!(
+                    "wrote {} snapshots, corpus {} failed",
+                    written,
+                    failed.join(", ")
+                )));
+            }
+            Ok(written)
+        }
+
+        /// How long parsed entries are served before a request triggers a refresh
+        const ENTRIES_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+        /// How often every corpus is refetched, well within `ENTRIES_TTL`
+        const REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+        /// Parsed entries of a corpus
+        struct Loaded {
+            entries: Arc<Vec<Entry>>,
+            /// When the entries were fetched, `None` for snapshots, which are stale from the start
+            fetched_at: Option<Instant>,
+            /// Whether a refresh is running, so stale reads start only one
+            refreshing: bool,
+        }
+
+        /// Loaded entries by corpus id
+        static ENTRIES: Mutex<BTreeMap<String, Loaded>> = Mutex::new(BTreeMap::new());
+
+        fn store(corpus: &dyn NameCorpus, entries: Arc<Vec<Entry>>, fetched_at: Option<Instant>) {
+            let loaded = Loaded {
+                entries,
+                fetched_at,
+                refreshing: false,
+            };
+            ENTRIES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(corpus.id().to_string(), loaded);
+        }
+
+        /// Loaded entries of a corpus, and whether the caller should refresh them. Only the
+        /// first caller to see them stale is told to
+        fn cached(corpus: &dyn NameCorpus) -> Option<(Arc<Vec<Entry>>, bool)> {
+            let mut entries = ENTRIES.lock().unwrap_or_else(PoisonError::into_inner);
+            let loaded = entries.get_mut(corpus.id())?;
+            let fresh = loaded
+                .fetched_at
+                .is_some_and(|fetched_at| fetched_at.elapsed() <= ENTRIES_TTL);
+            let refresh = !fresh && !loaded.refreshing && !offline();
+            loaded.refreshing |= refresh;
+            Some((loaded.entries.clone(), refresh))
+        }
+
+        /// Fetches and parses a corpus, keeping the entries only when there are any so a
+        /// failed or broken page keeps the last good copy
+        pub async fn refresh_entries(
+            client: &reqwest::Client,
+            corpus: &dyn NameCorpus,
+        ) -> Result<Arc<Vec<Entry>>, ServerFnError> {
+            let fetched = match corpus.fetch(client).await {
+                Ok(document) => Ok(corpus.parse(&document)),
+                Err(error) => Err(ServerFnError::from(error)),
+            };
+            let entries = fetched.and_then(|entries| match entries.is_empty() {
+                true => Err(ServerFnError::ServerError(format!(
+                    "No entries found for corpus {}",
+                    corpus.id()
+                ))),
+                false => Ok(Arc::new(entries)),
+            });
+            match &entries {
+                Ok(entries) => store(corpus, entries.clone(), Some(Instant::now())),
+                Err(_) => {
+                    let mut loaded = ENTRIES.lock().unwrap_or_else(PoisonError::into_inner);
+                    if let Some(loaded) = loaded.get_mut(corpus.id()) {
+                        loaded.refreshing = false;
+                    }
+                }
+            }
+            entries
+        }
+
+        fn snapshot_entries(corpus: &dyn NameCorpus) -> Option<Arc<Vec<Entry>>> {
+            let entries = Arc::new(snapshot(corpus)?);
+            store(corpus, entries.clone(), None);
+            Some(entries)
+        }
+
+        /// Loaded entries of a corpus, stale ones included while a refresh runs in the
+        /// background, fetching only when nothing is loaded and falling back to the bundled
+        /// snapshot
+        pub async fn load_entries(
+            client: &reqwest::Client,
+            corpus: &'static dyn NameCorpus,
+        ) -> Result<Arc<Vec<Entry>>, ServerFnError> {
+            if let Some((entries, refresh)) = cached(corpus) {
+                if refresh {
+                    let client = client.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = refresh_entries(&client, corpus).await {
+                            log::warn!("couldn't refresh corpus {}: {}", corpus.id(), error);
+                        }
+                    });
+                }
+                return Ok(entries);
+            }
+            if offline() {
+                return snapshot_entries(corpus).ok_or_else(|| {
+                    ServerFnError::ServerError(format!("No snapshot of corpus {}", corpus.id()))
+                });
+            }
+            match refresh_entries(client, corpus).await {
+                Ok(entries) => Ok(entries),
+                Err(error) => {
+                    log::warn!("couldn't fetch corpus {}: {}", corpus.id(), error);
+                    snapshot_entries(corpus).ok_or(error)
+                }
+            }
+        }
+
+        /// Refreshes every corpus on a schedule, starting right away to warm the cache
+        pub async fn refresh_corpora(client: reqwest::Client) {
+            if offline() {
+                log::info!("offline, serving corpus snapshots");
+                return;
+            }
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                for corpus in corpora() {
+                    if let Err(error) = refresh_entries(&client, corpus.as_ref()).await {
+                        log::warn!("couldn't refresh corpus {}: {}", corpus.id(), error);
+                    }
+                }
+            }
+        }
+    }
+}
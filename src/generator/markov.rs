@@ -0,0 +1,152 @@
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+/// Corpus a Markov model is trained on
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Hash, Eq)]
+pub enum MarkovCorpus {
+    /// Entry titles of the name corpus with this id, the server's default when empty
+    Corpus(String),
+    /// Every bundled generator word list
+    WordLists,
+}
+
+/// Markov synthesis settings, `order` is the number of characters of context
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct MarkovOptions {
+    pub corpus: MarkovCorpus,
+    pub order: usize,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub count: usize,
+    pub seed: Option<u64>,
+}
+
+impl Default for MarkovOptions {
+    fn default() -> Self {
+        MarkovOptions {
+            corpus: MarkovCorpus::Corpus(String::new()),
+            order: 3,
+            min_length: 4,
+            max_length: 10,
+            count: 48,
+            seed: None,
+        }
+    }
+}
+
+/// Lowercase alphabetic words of a corpus title, skipping short fillers
+pub fn corpus_words(title: &str) -> Vec<String> {
+    title
+        .split(|c: char| !c.is_alphabetic())
+        .map(|word| word.to_lowercase())
+        .filter(|word| word.chars().count() >= 3 && word != "operation")
+        .collect()
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use std::collections::{HashMap, HashSet};
+
+        const START: char = '^';
+        const END: char = '$';
+
+        /// Character level n-gram model
+        pub struct Markov {
+            order: usize,
+            transitions: HashMap<String, Vec<(char, usize)>>,
+            corpus: HashSet<String>,
+        }
+
+        impl Markov {
+            pub fn train<I: IntoIterator<Item = String>>(order: usize, words: I) -> Self {
+                let order = order.max(1);
+                let mut counts: HashMap<String, HashMap<char, usize>> = HashMap::new();
+                let mut corpus = HashSet::new();
+                for word in words {
+                    let padded: Vec<char> = std::iter::repeat_n(START, order)
+                        .chain(word.chars())
+                        .chain(std::iter::once(END))
+                        .collect();
+                    for window in padded.windows(order + 1) {
+                        let context: String = window[..order].iter().collect();
+                        *counts
+                            .entry(context)
+                            .or_default()
+                            .entry(window[order])
+                            .or_default() += 1;
+                    }
+                    corpus.insert(word);
+                }
+                let transitions = counts
+                    .into_iter()
+                    .map(|(context, next)| {
+                        // Sorted so a seed reproduces the same names across processes
+                        let mut next: Vec<_> = next.into_iter().collect();
+                        next.sort();
+                        (context, next)
+                    })
+                    .collect();
+                Markov { order, transitions, corpus }
+            }
+
+            fn next(&self, rng: &mut StdRng, context: &str) -> Option<char> {
+                let choices = self.transitions.get(context)?;
+                let total: usize = choices.iter().map(|(_, count)| count).sum();
+                let mut pick = rng.gen_range(0..total);
+                for (c, count) in choices {
+                    if pick < *count {
+                        return Some(*c);
+                    }
+                    pick -= count;
+                }
+                None
+            }
+
+            fn word(&self, rng: &mut StdRng, max_length: usize) -> Option<String> {
+                let mut context: Vec<char> = vec![START; self.order];
+                let mut word = String::new();
+                loop {
+                    let key: String = context[context.len() - self.order..].iter().collect();
+                    match self.next(rng, &key)? {
+                        END => return Some(word),
+                        c => {
+                            if word.chars().count() >= max_length {
+                                return None;
+                            }
+                            word.push(c);
+                            context.push(c);
+                        }
+                    }
+                }
+            }
+
+            /// Synthesizes unique words within the length bounds that are not part of the
+            /// corpus or any of `exclude`, returning the seed used alongside them
+            pub fn generate(&self, options: &MarkovOptions, exclude: &HashSet<String>) -> (u64, Vec<String>) {
+                let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut seen = HashSet::new();
+                let mut words = vec![];
+                for _ in 0..options.count * 100 {
+                    if words.len() >= options.count {
+                        break;
+                    }
+                    let Some(word) = self.word(&mut rng, options.max_length) else {
+                        continue;
+                    };
+                    if word.chars().count() < options.min_length
+                        || self.corpus.contains(&word)
+                        || exclude.contains(&word)
+                    {
+                        continue;
+                    }
+                    if seen.insert(word.clone()) {
+                        words.push(word);
+                    }
+                }
+                (seed, words)
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+pub mod markov;
+
+/// Bundled word lists candidates are combined from
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Hash, Eq, Copy)]
+pub enum WordList {
+    Adjectives,
+    Nouns,
+    Verbs,
+    Themes,
+}
+
+impl WordList {
+    pub const ALL: [WordList; 4] = [
+        WordList::Adjectives,
+        WordList::Nouns,
+        WordList::Verbs,
+        WordList::Themes,
+    ];
+
+    pub fn words(&self) -> Vec<&'static str> {
+        let text = match self {
+            WordList::Adjectives => include_str!("words/adjectives.txt"),
+            WordList::Nouns => include_str!("words/nouns.txt"),
+            WordList::Verbs => include_str!("words/verbs.txt"),
+            WordList::Themes => include_str!("words/themes.txt"),
+        };
+        text.lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .collect()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WordList::Adjectives => "adjective",
+            WordList::Nouns => "noun",
+            WordList::Verbs => "verb",
+            WordList::Themes => "theme",
+        }
+    }
+}
+
+/// Limits every generated candidate has to satisfy
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct Constraints {
+    pub max_length: Option<usize>,
+    pub max_syllables: Option<usize>,
+    pub starts_with_letter: bool,
+    pub no_hyphens: bool,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Constraints {
+            max_length: Some(14),
+            max_syllables: None,
+            starts_with_letter: true,
+            no_hyphens: false,
+        }
+    }
+}
+
+impl Constraints {
+    pub fn accepts(&self, name: &str) -> bool {
+        if self
+            .max_length
+            .is_some_and(|max| name.chars().count() > max)
+        {
+            return false;
+        }
+        if self.max_syllables.is_some_and(|max| syllables(name) > max) {
+            return false;
+        }
+        if self.starts_with_letter && !name.chars().next().is_some_and(|c| c.is_alphabetic()) {
+            return false;
+        }
+        if self.no_hyphens && name.contains('-') {
+            return false;
+        }
+        true
+    }
+}
+
+/// Word list generator settings, the seed makes a run reproducible
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+pub struct GeneratorOptions {
+    pub seed: Option<u64>,
+    pub count: usize,
+    #[serde(default)]
+    pub pattern: Vec<WordList>,
+    pub constraints: Constraints,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            seed: None,
+            count: 48,
+            pattern: vec![WordList::Adjectives, WordList::Nouns],
+            constraints: Constraints::default(),
+        }
+    }
+}
+
+/// Estimates syllables by counting vowel groups, ignoring a trailing silent `e`
+pub fn syllables(name: &str) -> usize {
+    let name = name.to_lowercase();
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in name.chars() {
+        let vowel = "aeiouy".contains(c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if count > 1 && name.ends_with('e') && !name.ends_with("le") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+        use std::collections::HashSet;
+
+        /// Combines one word from each list in the pattern until `count` candidates pass
+        /// the constraints, returning the seed used alongside them
+        pub fn generate(options: &GeneratorOptions) -> (u64, Vec<String>) {
+            let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+            let mut rng = StdRng::seed_from_u64(seed);
+            let lists: Vec<Vec<&str>> = options.pattern.iter().map(|list| list.words()).collect();
+            if lists.is_empty() || lists.iter().any(|words| words.is_empty()) {
+                return (seed, vec![]);
+            }
+
+            let separator = if options.constraints.no_hyphens { "" } else { "-" };
+            let mut seen = HashSet::new();
+            let mut names = vec![];
+            for _ in 0..options.count * 50 {
+                if names.len() >= options.count {
+                    break;
+                }
+                let name = lists
+                    .iter()
+                    .filter_map(|words| words.choose(&mut rng))
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(separator);
+                if options.constraints.accepts(&name) && seen.insert(name.clone()) {
+                    names.push(name);
+                }
+            }
+            (seed, names)
+        }
+    }
+}
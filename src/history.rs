@@ -0,0 +1,479 @@
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+#[cfg(feature = "ssr")]
+use crate::api::source_schema;
+use crate::api::CheckStatus;
+
+/// A stretch of time a name kept the same result on a source
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct Span {
+    pub status: CheckStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similar_to: Option<String>,
+    /// Unix time of the first check with this result
+    pub first_seen: u64,
+    /// Unix time of the latest check with this result
+    pub last_seen: u64,
+}
+
+impl Span {
+    /// What happened when the span started, given the one before it
+    pub fn describe(&self, previous: Option<&Span>) -> String {
+        let similar = || {
+            self.similar_to
+                .clone()
+                .unwrap_or("an existing name".to_string())
+        };
+        match (previous, self.status) {
+            (None, CheckStatus::Available) => "First seen available".to_string(),
+            (None, CheckStatus::Taken) => "First seen taken".to_string(),
+            (None, CheckStatus::Similar) => format!("First seen too similar to {}", similar()),
+            (Some(_), CheckStatus::Available) => "Became available".to_string(),
+            (Some(_), CheckStatus::Taken) => "Taken".to_string(),
+            (Some(_), CheckStatus::Similar) => format!("Became too similar to {}", similar()),
+            (_, CheckStatus::Error) => "Couldn't be checked".to_string(),
+        }
+    }
+}
+
+/// Every recorded result of a name on one source, oldest first
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct SourceHistory {
+    #[cfg_attr(feature = "ssr", schema(schema_with = source_schema))]
+    pub source: String,
+    pub spans: Vec<Span>,
+}
+
+/// Recorded results of a name on every source it was checked against
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct NameHistory {
+    pub name: String,
+    pub sources: Vec<SourceHistory>,
+}
+
+fn datetime(secs: u64) -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(secs as i64).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
+
+/// Unix time as an ISO 8601 UTC timestamp, e.g. `2024-01-31T09:05:00Z`
+pub fn timestamp(secs: u64) -> String {
+    let time = datetime(secs);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        time.date(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
+
+/// Unix time as a readable UTC date and time, e.g. `2024-01-31 09:05 UTC`
+pub fn display_time(secs: u64) -> String {
+    let time = datetime(secs);
+    format!(
+        "{} {:02}:{:02} UTC",
+        time.date(),
+        time.hour(),
+        time.minute()
+    )
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl NameHistory {
+    /// One row per span, with a header
+    pub fn to_csv(&self) -> String {
+        let mut csv = "name,source,status,similar_to,first_seen,last_seen\n".to_string();
+        for source in &self.sources {
+            for span in &source.spans {
+                let status = serde_json::to_value(span.status)
+                    .ok()
+                    .and_then(|status| status.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                let fields = [
+                    self.name.clone(),
+                    source.source.clone(),
+                    status,
+                    span.similar_to.clone().unwrap_or_default(),
+                    timestamp(span.first_seen),
+                    timestamp(span.last_seen),
+                ];
+                csv += &fields.map(|field| csv_field(&field)).join(",");
+                csv += "\n";
+            }
+        }
+        csv
+    }
+}
+
+cfg_if! { if #[cfg(feature = "ssr")] {
+    use axum::{
+        extract::{Path, RawQuery, State},
+        http::header,
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use std::{
+        collections::BTreeMap,
+        fs::OpenOptions,
+        io::{BufRead, BufReader, ErrorKind, Write},
+        path::{Path as FilePath, PathBuf},
+        sync::{Arc, Mutex, PoisonError},
+        time::Duration,
+    };
+    use tokio::sync::mpsc;
+
+    use crate::api::{parse_name, BadRequest, NameResult, SourceResult};
+    use crate::components::Sources;
+    use crate::store::unix_now;
+
+    /// Days a span is kept after it was last seen, a year when unset
+    pub const RETENTION_VAR: &str = "NAMEIT_HISTORY_DAYS";
+
+    /// How long new lines gather before they're appended together
+    const FLUSH_DELAY: Duration = Duration::from_secs(1);
+    /// How often the log is rewritten with one line per span, dropping expired ones
+    const COMPACT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Spans by lowercased name, then by source key
+    type Spans = BTreeMap<String, BTreeMap<String, Vec<Span>>>;
+
+    /// One line of the log, a span of a name on a source
+    #[derive(Deserialize, Serialize)]
+    struct LogLine {
+        name: String,
+        source: String,
+        #[serde(flatten)]
+        span: Span,
+    }
+
+    /// Every explicit check result over time, kept in memory and appended to the
+    /// `history.jsonl` log in the data directory
+    #[derive(Debug, Clone)]
+    pub struct History {
+        spans: Arc<Mutex<Spans>>,
+        log: mpsc::UnboundedSender<LogLine>,
+    }
+
+    /// Folds a span into the ones of its name and source, extending the latest when
+    /// the result didn't change and ignoring it when it's older than the latest
+    fn merge(names: &mut Spans, name: &str, source: &str, span: Span) -> bool {
+        let spans = names
+            .entry(name.to_lowercase())
+            .or_default()
+            .entry(source.to_string())
+            .or_default();
+        match spans.last_mut() {
+            Some(last) if last.status == span.status && last.similar_to == span.similar_to => {
+                last.first_seen = last.first_seen.min(span.first_seen);
+                last.last_seen = last.last_seen.max(span.last_seen);
+            }
+            Some(last) if last.last_seen > span.first_seen => return false,
+            _ => spans.push(span),
+        }
+        true
+    }
+
+    /// Replays the log, skipping lines a crash cut short
+    fn replay(path: &FilePath) -> std::io::Result<Spans> {
+        let mut names = Spans::new();
+        let file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(names),
+            Err(error) => return Err(error),
+        };
+        for line in BufReader::new(file).lines() {
+            match serde_json::from_str::<LogLine>(&line?) {
+                Ok(line) => {
+                    merge(&mut names, &line.name, &line.source, line.span);
+                }
+                Err(error) => log::warn!("skipping a broken check history line: {}", error),
+            }
+        }
+        Ok(names)
+    }
+
+    /// Drops spans last seen before `cutoff`, and names left without any
+    fn expire(names: &mut Spans, cutoff: u64) {
+        names.retain(|_, sources| {
+            sources.retain(|_, spans| {
+                spans.retain(|span| span.last_seen >= cutoff);
+                !spans.is_empty()
+            });
+            !sources.is_empty()
+        });
+    }
+
+    fn append(path: &FilePath, lines: &[LogLine]) -> std::io::Result<()> {
+        let mut text = String::new();
+        for line in lines {
+            text += &serde_json::to_string(line)?;
+            text.push('\n');
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(text.as_bytes())
+    }
+
+    /// Rewrites the log with one line per span, written aside and renamed over
+    fn compact(path: &FilePath, names: &Spans) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let lines = names
+            .iter()
+            .flat_map(|(name, sources)| {
+                sources.iter().flat_map(move |(source, spans)| {
+                    spans.iter().map(move |span| LogLine {
+                        name: name.clone(),
+                        source: source.clone(),
+                        span: span.clone(),
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        let temporary = path.with_extension("jsonl.tmp");
+        let _ = std::fs::remove_file(&temporary);
+        append(&temporary, &lines)?;
+        std::fs::rename(&temporary, path)
+    }
+
+    /// Appends lines as they come, batching the ones sent close together, and compacts the
+    /// log every [`COMPACT_INTERVAL`] so it doesn't grow without bound. Both run here so a
+    /// compaction never races an append
+    async fn write_log(
+        path: PathBuf,
+        spans: Arc<Mutex<Spans>>,
+        retention: u64,
+        mut lines: mpsc::UnboundedReceiver<LogLine>,
+    ) {
+        let mut next_compaction = tokio::time::Instant::now() + COMPACT_INTERVAL;
+        loop {
+            let batch = match tokio::time::timeout_at(next_compaction, lines.recv()).await {
+                Ok(Some(line)) => {
+                    tokio::time::sleep(FLUSH_DELAY).await;
+                    let mut batch = vec![line];
+                    while let Ok(line) = lines.try_recv() {
+                        batch.push(line);
+                    }
+                    batch
+                }
+                Ok(None) => return,
+                Err(_) => {
+                    next_compaction += COMPACT_INTERVAL;
+                    // Lines still queued were merged already, appending them again after the
+                    // compaction only repeats what replay folds back together
+                    let names = {
+                        let mut names = spans.lock().unwrap_or_else(PoisonError::into_inner);
+                        expire(&mut names, unix_now().saturating_sub(retention));
+                        names.clone()
+                    };
+                    let path = path.clone();
+                    if let Err(error) = blocking(move || compact(&path, &names)).await {
+                        log::error!("couldn't compact the check history: {}", error);
+                    }
+                    continue;
+                }
+            };
+            let path = path.clone();
+            if let Err(error) = blocking(move || append(&path, &batch)).await {
+                log::error!("couldn't save the check history: {}", error);
+            }
+        }
+    }
+
+    async fn blocking(
+        write: impl FnOnce() -> std::io::Result<()> + Send + 'static,
+    ) -> std::io::Result<()> {
+        tokio::task::spawn_blocking(write)
+            .await
+            .unwrap_or_else(|error| Err(std::io::Error::other(error)))
+    }
+
+    impl History {
+        /// Loads the log, drops expired spans and compacts it, then starts the task
+        /// appending to it. Must be called inside the Tokio runtime
+        pub fn open(path: PathBuf) -> std::io::Result<Self> {
+            let mut names = replay(&path)?;
+            let days = std::env::var(RETENTION_VAR)
+                .ok()
+                .and_then(|days| days.parse::<u64>().ok())
+                .unwrap_or(365);
+            let retention = days * 24 * 60 * 60;
+            expire(&mut names, unix_now().saturating_sub(retention));
+            compact(&path, &names)?;
+
+            let spans = Arc::new(Mutex::new(names));
+            let (log, lines) = mpsc::unbounded_channel();
+            tokio::spawn(write_log(path, spans.clone(), retention, lines));
+            Ok(History { spans, log })
+        }
+
+        /// Adds results to the history, extending the latest span when nothing changed.
+        /// Only explicit lookups belong here, not names browsed in bulk
+        pub fn record<'a>(&self, results: impl IntoIterator<Item = (&'a str, &'a SourceResult)>) {
+            let now = unix_now();
+            let mut names = self.spans.lock().unwrap_or_else(PoisonError::into_inner);
+            // Failed checks say nothing about the name
+            for (name, result) in results.into_iter().filter(|(_, result)| result.status != CheckStatus::Error) {
+                // Cached results were seen when they were fetched
+                let seen = now.saturating_sub(result.age_secs);
+                let span = Span {
+                    status: result.status,
+                    similar_to: result.similar_to.clone(),
+                    first_seen: seen,
+                    last_seen: seen,
+                };
+                if merge(&mut names, name, &result.source, span.clone()) {
+                    let line = LogLine {
+                        name: name.to_lowercase(),
+                        source: result.source.clone(),
+                        span,
+                    };
+                    if self.log.send(line).is_err() {
+                        log::error!("couldn't save the check history: the writer stopped");
+                    }
+                }
+            }
+        }
+
+        pub fn record_names(&self, results: &[NameResult]) {
+            self.record(results.iter().flat_map(|name| {
+                name.results.iter().map(|result| (name.name.as_str(), result))
+            }));
+        }
+
+        /// History of a name, sources in the order [`Sources::ALL`] lists them
+        pub fn get(&self, name: &str) -> NameHistory {
+            let mut sources = self
+                .spans
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(&name.to_lowercase())
+                .map(|sources| {
+                    sources
+                        .iter()
+                        .map(|(source, spans)| SourceHistory {
+                            source: source.clone(),
+                            spans: spans.clone(),
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            sources.sort_by_key(|history| {
+                Sources::ALL
+                    .iter()
+                    .position(|source| source.key() == history.source)
+            });
+            NameHistory {
+                name: name.to_string(),
+                sources,
+            }
+        }
+    }
+
+    /// Exports the history of a name
+    #[utoipa::path(
+        get,
+        path = "/v1/history/{name}",
+        tag = "history",
+        params(
+            ("name" = String, Path, description = "Name to export the history of"),
+            ("format" = Option<String>, Query, description = "`json`, the default, or `csv`"),
+        ),
+        responses(
+            (status = 200, description = "Every recorded result, one span per unchanged stretch", body = NameHistory),
+            (status = 400, description = "The name is invalid", body = ApiError),
+        )
+    )]
+    pub async fn export_history(
+        State(history): State<History>,
+        Path(name): Path<String>,
+        RawQuery(query): RawQuery,
+    ) -> Result<Response, BadRequest> {
+        let name = parse_name(name.trim_start_matches('/'))?;
+        let csv = form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .any(|(key, value)| key == "format" && value == "csv");
+        let history = history.get(&name);
+        if !csv {
+            return Ok(Json(history).into_response());
+        }
+        let file = format!("attachment; filename=\"{}-history.csv\"", name.replace(['/', '"'], "-"));
+        Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, file),
+            ],
+            history.to_csv(),
+        )
+            .into_response())
+    }
+}}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn line(status: CheckStatus, seen: u64) -> LogLine {
+        LogLine {
+            name: "nameit".to_string(),
+            source: "npm".to_string(),
+            span: Span {
+                status,
+                similar_to: None,
+                first_seen: seen,
+                last_seen: seen,
+            },
+        }
+    }
+
+    #[test]
+    fn replay_folds_lines_into_spans() {
+        let path =
+            std::env::temp_dir().join(format!("nameit-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        append(
+            &path,
+            &[
+                line(CheckStatus::Taken, 10),
+                line(CheckStatus::Taken, 20),
+                line(CheckStatus::Available, 30),
+            ],
+        )
+        .unwrap();
+        // A crash halfway through a write
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"name\":")
+            .unwrap();
+
+        let mut names = replay(&path).unwrap();
+        let spans = &names["nameit"]["npm"];
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].first_seen, spans[0].last_seen), (10, 20));
+        assert_eq!(spans[1].status, CheckStatus::Available);
+
+        expire(&mut names, 25);
+        assert_eq!(names["nameit"]["npm"].len(), 1);
+        compact(&path, &names).unwrap();
+        assert_eq!(replay(&path).unwrap(), names);
+        expire(&mut names, 40);
+        assert!(names.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
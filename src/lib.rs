@@ -1,8 +1,17 @@
 use cfg_if::cfg_if;
+pub mod api;
 pub mod app;
+pub mod badge;
+pub mod chat;
 pub mod components;
+pub mod corpus;
+pub mod generator;
+pub mod history;
+pub mod search;
 pub mod signals;
 pub mod state;
+pub mod store;
+pub mod watch;
 
 pub mod fileserv;
 
@@ -23,9 +23,14 @@ cfg_if! {
         use nameit::app::*;
         use nameit::state::*;
         use nameit::fileserv::file_and_error_handler;
+        use nameit::api::{check_batch, check_one, docs};
+        use nameit::badge::badge;
+        use nameit::chat::{mattermost_command, slack_command};
+        use nameit::watch::{list_watched, unwatch, watch};
+        use nameit::history::{export_history, History};
 
         async fn server_fn_handler(
-            State(cache): State<AppCache>,
+            State(state): State<AppState>,
             path: Path<String>,
             headers: HeaderMap,
             raw_query: RawQuery,
@@ -37,18 +42,24 @@ cfg_if! {
                 headers,
                 raw_query,
                 move || {
-                    provide_context(cache.clone());
+                    provide_context(state.cache.clone());
+                    provide_context(state.client.clone());
+                    provide_context(state.history.clone());
+                    provide_context(state.limit.clone());
                 },
                 request,
             )
             .await
         }
 
-        async fn leptos_routes_handler(State(cache): State<AppCache>, State(routes): State<AppRoutes>, State(leptos_options): State<LeptosOptions>, req: Request<AxumBody>) -> Response{
+        async fn leptos_routes_handler(State(cache): State<AppCache>, State(client): State<reqwest::Client>, State(history): State<History>, State(limit): State<CheckLimit>, State(routes): State<AppRoutes>, State(leptos_options): State<LeptosOptions>, req: Request<AxumBody>) -> Response{
             let handler = leptos_axum::render_route_with_context(leptos_options,
             routes.0,
             move || {
                 provide_context(cache.clone());
+                provide_context(client.clone());
+                provide_context(history.clone());
+                provide_context(limit.clone());
             },
             App
         );
@@ -60,6 +71,29 @@ cfg_if! {
         async fn main() {
             simple_logger::init_with_level(log::Level::Info).expect("couldn't initialize logging");
 
+            // Corpus definitions, the ones compiled in are used when the file is missing
+            let corpora = std::env::var("NAMEIT_CORPORA").unwrap_or("corpora.toml".to_string());
+            if std::path::Path::new(&corpora).exists() {
+                let count = nameit::corpus::load_corpora(corpora.as_ref())
+                    .expect("couldn't load corpus definitions");
+                log::info!("loaded {} corpora from {}", count, corpora);
+            }
+
+            // `nameit-server snapshot [dir]` regenerates the bundled corpus snapshots
+            let mut args = std::env::args().skip(1);
+            if args.next().as_deref() == Some("snapshot") {
+                let dir = args.next().unwrap_or("corpora/snapshots".to_string());
+                let client = nameit_core::client().expect("couldn't build the HTTP client");
+                let count = nameit::corpus::write_snapshots(&client, dir.as_ref())
+                    .await
+                    .expect("couldn't write corpus snapshots");
+                log::info!("wrote {} snapshots to {}", count, dir);
+                return;
+            }
+            if nameit::corpus::offline() {
+                nameit::corpus::check_snapshots().expect("can't serve every corpus offline");
+            }
+
 
             // Setting get_configuration(None) means we'll be using cargo-leptos's env values
             // For deployment these variables are:
@@ -71,6 +105,14 @@ cfg_if! {
             let addr = conf.leptos_options.site_addr.clone();
             let state = AppState::new(routes.clone(), conf.leptos_options);
 
+            tokio::spawn(nameit::corpus::refresh_corpora(state.client.clone()));
+            tokio::spawn(nameit::watch::run_watchlist(
+                state.client.clone(),
+                state.watchlist.clone(),
+                state.history.clone(),
+                state.limit.clone(),
+            ));
+
             // build our application with a route
             let app = Router::new()
                 // .route("/api/*fn_name", post(leptos_axum::handle_server_fns))
@@ -78,6 +120,13 @@ cfg_if! {
                 // .fallback(file_and_error_handler)
                 // .with_state(leptos_options);
                 .route("/api/*fn_name", post(server_fn_handler))
+                .route("/v1/check", get(check_one).post(check_batch))
+                .route("/v1/watch", get(list_watched).post(watch).delete(unwatch))
+                .route("/v1/history/*name", get(export_history))
+                .route("/badge/:source/*name", get(badge))
+                .route("/chat/slack", post(slack_command))
+                .route("/chat/mattermost", post(mattermost_command))
+                .merge(docs())
                 .leptos_routes_with_handler(routes, get(leptos_routes_handler) )
                 .fallback(file_and_error_handler)
                 .with_state(state);
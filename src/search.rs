@@ -0,0 +1,186 @@
+//! Typo tolerant fuzzy matching of a query against corpus titles
+
+/// How well a query matches a text, with the char indices of the matched characters
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+fn is_word_start(text: &[char], index: usize) -> bool {
+    index == 0 || !text[index - 1].is_alphanumeric()
+}
+
+/// Best in order match of every query character, trying each start of the first one
+fn subsequence(query: &[char], text: &[char]) -> Option<FuzzyMatch> {
+    let mut best: Option<FuzzyMatch> = None;
+    for start in (0..text.len()).filter(|index| text[*index] == query[0]) {
+        let mut positions = vec![start];
+        let mut index = start + 1;
+        for c in &query[1..] {
+            while index < text.len() && text[index] != *c {
+                index += 1;
+            }
+            if index == text.len() {
+                break;
+            }
+            positions.push(index);
+            index += 1;
+        }
+        if positions.len() < query.len() {
+            // Later starts leave even less text to match
+            break;
+        }
+
+        let mut score = 0;
+        for (i, position) in positions.iter().enumerate() {
+            score += 16;
+            if is_word_start(text, *position) {
+                score += 12;
+            }
+            if i > 0 {
+                match position - positions[i - 1] - 1 {
+                    0 => score += 8,
+                    gap => score -= gap as i64,
+                }
+            }
+        }
+        if start == 0 {
+            score += 10;
+        }
+        if best.as_ref().is_none_or(|best| score > best.score) {
+            best = Some(FuzzyMatch { score, positions });
+        }
+    }
+    best
+}
+
+/// Optimal string alignment distance, counting adjacent transpositions as one edit
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Closest word, or word prefix, within a few typos of the query
+fn typo(query: &[char], text: &[char]) -> Option<FuzzyMatch> {
+    let allowed = match query.len() {
+        0..=2 => return None,
+        3..=5 => 1,
+        _ => 2,
+    };
+    let mut best: Option<(usize, usize, usize)> = None;
+    let mut start = 0;
+    while start < text.len() {
+        if !text[start].is_alphanumeric() {
+            start += 1;
+            continue;
+        }
+        let end = (start..text.len())
+            .find(|index| !text[*index].is_alphanumeric())
+            .unwrap_or(text.len());
+        let word = &text[start..end];
+        let prefix = &word[..word.len().min(query.len())];
+        let distance = edit_distance(query, word).min(edit_distance(query, prefix));
+        if best.is_none_or(|(best, _, _)| distance < best) {
+            best = Some((distance, start, start + prefix.len()));
+        }
+        start = end;
+    }
+
+    let (distance, start, end) = best.filter(|(distance, _, _)| *distance <= allowed)?;
+    Some(FuzzyMatch {
+        // Always ranked below matches without typos of the same length
+        score: 8 * query.len() as i64 - 24 * distance as i64,
+        positions: (start..end)
+            .filter(|index| query.contains(&text[*index]))
+            .collect(),
+    })
+}
+
+/// Matches `query` against `text` ignoring case and whitespace in the query, allowing a
+/// few typos when the characters don't all appear in order
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return None;
+    }
+    // Per char lowercasing keeps the indices valid for the original text
+    let text: Vec<char> = text
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+
+    subsequence(&query, &text)
+        .map(|mut found| {
+            if found.positions.len() == text.len() {
+                found.score += 20;
+            }
+            found
+        })
+        .or_else(|| typo(&query, &text))
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_ranks_above_subsequence() {
+        let prefix = fuzzy_match("orb", "Orbit").unwrap();
+        let scattered = fuzzy_match("orb", "Overlord Bay").unwrap();
+        assert!(prefix.score > scattered.score);
+        // Typos rank below any in order match of the same query
+        let typo = fuzzy_match("obr", "Orbit").unwrap();
+        assert!(scattered.score > typo.score);
+    }
+
+    #[test]
+    fn one_transposition() {
+        let found = fuzzy_match("tocr", "Torch").unwrap();
+        assert_eq!(found.positions, [0, 1, 2, 3]);
+        assert!(fuzzy_match("ovelrord", "Operation Overlord").is_some());
+    }
+
+    #[test]
+    fn beyond_edit_budget() {
+        assert_eq!(fuzzy_match("tcxr", "Torch"), None);
+        assert_eq!(fuzzy_match("xyzzy", "Operation Overlord"), None);
+        // Too short for any typo
+        assert_eq!(fuzzy_match("ba", "Abc"), None);
+        assert_eq!(fuzzy_match(" ", "Torch"), None);
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(
+            fuzzy_match("o b", "Operation Barbarossa")
+                .unwrap()
+                .positions,
+            [0, 10]
+        );
+        assert_eq!(
+            fuzzy_match("TORCH", "Torch").unwrap().positions,
+            [0, 1, 2, 3, 4]
+        );
+    }
+}
@@ -1,93 +1,68 @@
 use cfg_if::cfg_if;
 
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-    time::{Duration, Instant},
-};
-
-#[derive(Debug, Clone)]
-pub struct Cached<T> {
-    value: T,
-    duration: Duration,
-    cached_at: Instant,
-}
-
-#[derive(Debug, Clone)]
-pub enum CacheData {
-    Exists(Cached<bool>), // I used String here as a placeholder for your cached data type
-    Html(String),
-    Expired,
-    // NotFound
-}
-
-#[derive(Debug, Clone)]
-pub struct AppCache(Arc<Mutex<HashMap<String, CacheData>>>);
-
-impl AppCache {
-    pub fn get_value(&self, key: String) -> Option<CacheData> {
-        match self.0.lock() {
-            Ok(v) => match v.get(&key) {
-                Some(y) => Some(y.clone()),
-                _ => None,
-            },
-            _ => None,
-        }
-    }
-
-    pub fn set_value(&self, key: String, data: CacheData) -> Option<CacheData> {
-        match self.0.lock() {
-            Ok(mut v) => v.insert(key, data),
-            _ => None,
-        }
-    }
-
-    pub fn get_exists(&self, key: String) -> Option<bool> {
-        let value = self.get_value(key.clone())?;
-        match value {
-            CacheData::Exists(cached) => {
-                if cached.cached_at.elapsed() > cached.duration {
-                    self.set_value(key, CacheData::Expired);
-                    return None;
-                }
-                Some(cached.value.clone())
-            }
-            _ => None,
-        }
-    }
-
-    pub fn set_exists(&self, key: String, exists: bool) -> Option<()> {
-        self.set_value(
-            key,
-            CacheData::Exists(Cached {
-                value: exists,
-                duration: Duration::from_secs(86_400),
-                cached_at: Instant::now(),
-            }),
-        )?;
-        Some(())
-    }
-}
+pub use nameit_core::cache::{Cache as AppCache, CacheData, Cached};
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
         use leptos_router::RouteListing;
         use leptos::LeptosOptions;
         use axum::extract::FromRef;
+        use std::sync::Arc;
+        use tokio::sync::Semaphore;
+
+        use crate::history::History;
+        use crate::store::data_path;
+        use crate::watch::Watchlist;
 
         #[derive(Debug, Clone)]
         pub struct AppRoutes(pub Vec<RouteListing>);
 
+        /// Most checks the whole server keeps in flight at once
+        pub const MAX_CONCURRENT_CHECKS: usize = 32;
+
+        /// Permits every caller of `check_source` acquires, shared across requests
+        #[derive(Debug, Clone)]
+        pub struct CheckLimit(pub Arc<Semaphore>);
+
         #[derive(Debug, Clone)]
         pub struct AppState {
             pub leptos_options: LeptosOptions,
             pub routes: AppRoutes,
             pub cache: AppCache,
+            /// Shared by every check and scraper
+            pub client: reqwest::Client,
+            pub watchlist: Watchlist,
+            pub history: History,
+            pub limit: CheckLimit,
         }
 
         impl FromRef<AppState> for AppCache {
             fn from_ref(app_state: &AppState) -> AppCache {
-                AppCache(Arc::clone(&app_state.cache.0))
+                app_state.cache.clone()
+            }
+        }
+
+        impl FromRef<AppState> for reqwest::Client {
+            fn from_ref(app_state: &AppState) -> reqwest::Client {
+                app_state.client.clone()
+            }
+        }
+
+        impl FromRef<AppState> for Watchlist {
+            fn from_ref(app_state: &AppState) -> Watchlist {
+                app_state.watchlist.clone()
+            }
+        }
+
+        impl FromRef<AppState> for History {
+            fn from_ref(app_state: &AppState) -> History {
+                app_state.history.clone()
+            }
+        }
+
+        impl FromRef<AppState> for CheckLimit {
+            fn from_ref(app_state: &AppState) -> CheckLimit {
+                app_state.limit.clone()
             }
         }
 
@@ -108,7 +83,13 @@ cfg_if! {
                 AppState {
                     leptos_options,
                     routes: AppRoutes(routes),
-                    cache: AppCache(Arc::new(Mutex::new(HashMap::new()))),
+                    cache: AppCache::new(),
+                    client: nameit_core::client().expect("couldn't build the HTTP client"),
+                    watchlist: Watchlist::open(data_path("watchlist.json"))
+                        .expect("couldn't load the watchlist"),
+                    history: History::open(data_path("history.jsonl"))
+                        .expect("couldn't load the check history"),
+                    limit: CheckLimit(Arc::new(Semaphore::new(MAX_CONCURRENT_CHECKS))),
                 }
             }
         }
@@ -0,0 +1,77 @@
+use cfg_if::cfg_if;
+
+cfg_if! { if #[cfg(feature = "ssr")] {
+    use serde::{de::DeserializeOwned, Serialize};
+    use std::{
+        io::ErrorKind,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, PoisonError},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    /// Directory the persistent stores live in, `data` when unset
+    pub const DATA_DIR_VAR: &str = "NAMEIT_DATA_DIR";
+
+    /// Path of a file in the data directory
+    pub fn data_path(file: &str) -> PathBuf {
+        let dir = std::env::var(DATA_DIR_VAR).unwrap_or("data".to_string());
+        Path::new(&dir).join(file)
+    }
+
+    /// Seconds since the Unix epoch, the timestamp stored records carry
+    pub fn unix_now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default()
+    }
+
+    /// A value kept in memory and written back to its JSON file after every update
+    #[derive(Debug)]
+    pub struct JsonStore<T> {
+        path: Arc<PathBuf>,
+        value: Arc<Mutex<T>>,
+    }
+
+    impl<T> Clone for JsonStore<T> {
+        fn clone(&self) -> Self {
+            JsonStore {
+                path: self.path.clone(),
+                value: self.value.clone(),
+            }
+        }
+    }
+
+    impl<T: Serialize + DeserializeOwned + Default> JsonStore<T> {
+        /// Loads the file, starting from the default value when it doesn't exist yet
+        pub fn open(path: PathBuf) -> std::io::Result<Self> {
+            let value = match std::fs::read(&path) {
+                Ok(bytes) => serde_json::from_slice(&bytes)?,
+                Err(error) if error.kind() == ErrorKind::NotFound => T::default(),
+                Err(error) => return Err(error),
+            };
+            Ok(JsonStore {
+                path: Arc::new(path),
+                value: Arc::new(Mutex::new(value)),
+            })
+        }
+
+        pub fn read<R>(&self, read: impl FnOnce(&T) -> R) -> R {
+            read(&self.value.lock().unwrap_or_else(PoisonError::into_inner))
+        }
+
+        /// Changes the value and saves it, holding the lock so writes land in order
+        pub fn update<R>(&self, update: impl FnOnce(&mut T) -> R) -> std::io::Result<R> {
+            let mut value = self.value.lock().unwrap_or_else(PoisonError::into_inner);
+            let result = update(&mut value);
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // Written aside and renamed over, so a crash never leaves half a file
+            let temporary = self.path.with_extension("json.tmp");
+            std::fs::write(&temporary, serde_json::to_vec_pretty(&*value)?)?;
+            std::fs::rename(&temporary, self.path.as_ref())?;
+            Ok(result)
+        }
+    }
+}}